@@ -1,8 +1,6 @@
 use async_std::task;
 use trace::trace;
 
-trace::init_depth_var!();
-
 #[trace]
 async fn squared(x: i32) -> i32 {
     x * x
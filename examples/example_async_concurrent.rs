@@ -0,0 +1,48 @@
+use std::future::{self, Future};
+use std::task::Poll;
+
+use async_std::task;
+use trace::trace;
+
+fn main() {
+    task::block_on(run());
+}
+
+#[trace]
+async fn run() {
+    let mut a = Box::pin(worker("a", 2));
+    let mut b = Box::pin(worker("b", 1));
+    let (mut a_done, mut b_done) = (false, false);
+
+    // Poll both workers concurrently on the same thread
+    future::poll_fn(|cx| {
+        a_done = a_done || a.as_mut().poll(cx).is_ready();
+        b_done = b_done || b.as_mut().poll(cx).is_ready();
+        if a_done && b_done {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+#[trace(poll_events)]
+async fn worker(name: &str, yields: u32) -> u32 {
+    for _ in 0..yields {
+        task::yield_now().await;
+    }
+    squared(yields)
+}
+
+#[trace]
+fn squared(x: u32) -> u32 {
+    x * x
+}
+
+#[cfg(test)]
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_async_concurrent, main());
@@ -0,0 +1,47 @@
+use std::{env, fs, process, thread};
+use trace::{trace, ChromeSink};
+
+fn main() {
+    // Open this file in `chrome://tracing` or https://ui.perfetto.dev
+    let path = env::temp_dir().join(format!("example_chrome-{}.json", process::id()));
+    let guard = trace::sink_scope(ChromeSink::create(&path).unwrap());
+    for (name, n) in [("first", 3), ("second", 2)] {
+        thread::Builder::new()
+            .name(name.to_owned())
+            .spawn(move || fib(n))
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+    // Dropping the guard removes the sink, which closes the trace
+    drop(guard);
+
+    let trace: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    fs::remove_file(&path).unwrap();
+    for record in trace.as_array().unwrap() {
+        println!(
+            "{} {} tid={} {}",
+            record["ph"].as_str().unwrap(),
+            record["name"].as_str().unwrap(),
+            record["tid"],
+            record["args"]
+        );
+    }
+}
+
+#[trace]
+fn fib(n: u32) -> u32 {
+    if n < 2 {
+        n
+    } else {
+        fib(n - 1) + fib(n - 2)
+    }
+}
+
+#[cfg(test)]
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_chrome, main());
@@ -1,7 +1,5 @@
 use trace::trace;
 
-trace::init_depth_var!();
-
 fn main() {
     foo(5, 4);
 }
@@ -0,0 +1,41 @@
+use trace::trace;
+
+fn main() {
+    foo(1).unwrap();
+    println!("{}", bar(-1));
+    baz();
+}
+
+// Only traced in builds with debug assertions, so not with `cargo run --release`
+#[trace(debug_only)]
+fn foo(a: i32) -> Result<i32, String> {
+    let b = bar(a);
+    Ok(b.checked_mul(2).ok_or("overflow")?)
+}
+
+#[trace(debug_only)]
+fn bar(a: i32) -> i32 {
+    if a < 0 {
+        return 0;
+    }
+    a + 1
+}
+
+// Only `debug_only` with the `debug-only` feature of `trace`
+#[trace]
+fn baz() {}
+
+#[cfg(test)]
+#[macro_use]
+mod trace_test;
+
+#[cfg(all(test, debug_assertions))]
+trace_test!(test_debug_only, main());
+
+// Run with `cargo test --release`
+#[cfg(all(test, not(debug_assertions), not(feature = "debug-only")))]
+trace_test!(test_debug_only_release, main());
+
+// Run with `cargo test --release --features debug-only`
+#[cfg(all(test, not(debug_assertions), feature = "debug-only"))]
+trace_test!(test_debug_only_feature_release, main());
@@ -0,0 +1,36 @@
+use std::num::ParseIntError;
+use trace::trace;
+
+fn main() {
+    let _ = parse_sum("1", "2");
+    let _ = parse_sum("1", "x");
+    clamp(20);
+    clamp(5);
+}
+
+#[trace]
+fn parse(s: &str) -> Result<i32, ParseIntError> {
+    s.parse()
+}
+
+#[trace]
+fn parse_sum(a: &str, b: &str) -> Result<i32, ParseIntError> {
+    let a = parse(a)?;
+    let b = parse(b)?;
+    Ok(a + b)
+}
+
+#[trace]
+fn clamp(x: u32) -> u32 {
+    if x > 10 {
+        return 10;
+    }
+    x
+}
+
+#[cfg(test)]
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_early_return, main());
@@ -1,9 +1,6 @@
-use std::cell::Cell;
-use trace::trace;
+#![allow(clippy::disallowed_names)]
 
-thread_local! {
-    static DEPTH: Cell<usize> = Cell::new(0);
-}
+use trace::trace;
 
 fn main() {
     let foo = Foo;
@@ -0,0 +1,43 @@
+use std::thread;
+use trace::trace;
+
+fn main() {
+    trace::set_enabled(false);
+    foo(1);
+
+    {
+        let _guard = trace::enabled_scope();
+        foo(2);
+    }
+    foo(3);
+
+    thread::spawn(|| {
+        let _guard = trace::thread_enabled_scope();
+        foo(4);
+    })
+    .join()
+    .unwrap();
+
+    trace::set_enabled(true);
+    trace::set_thread_enabled(false);
+    foo(5);
+    trace::reset_thread_enabled();
+    foo(6);
+}
+
+#[trace]
+fn foo(a: i32) -> i32 {
+    bar(a) + 1
+}
+
+#[trace]
+fn bar(a: i32) -> i32 {
+    a * 2
+}
+
+#[cfg(test)]
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_enabled, main());
@@ -0,0 +1,53 @@
+use logger::{Log, Logger};
+use std::env;
+use trace::trace;
+
+fn main() {
+    // Usually set when running the program, e.g. `RUST_TRACE=parser::*,!parser::lex_char`.
+    // `Logger::log` matches the method of the `impl Log for Logger` as well.
+    env::set_var("RUST_TRACE", "parser::*,!parser::lex_char,Logger::log");
+
+    let tokens = parser::parse("ab");
+    print_tokens(&tokens);
+    Logger.log("done");
+}
+
+#[trace]
+fn print_tokens(tokens: &[char]) {
+    println!("{:?}", tokens);
+}
+
+#[trace]
+mod parser {
+    pub fn parse(input: &str) -> Vec<char> {
+        input.chars().map(lex_char).collect()
+    }
+
+    fn lex_char(c: char) -> char {
+        c.to_ascii_uppercase()
+    }
+}
+
+mod logger {
+    use trace::trace;
+
+    pub trait Log {
+        fn log(&self, message: &str);
+    }
+
+    pub struct Logger;
+
+    #[trace]
+    impl Log for Logger {
+        fn log(&self, message: &str) {
+            println!("{}", message);
+        }
+    }
+}
+
+#[cfg(test)]
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_filter, main());
@@ -0,0 +1,48 @@
+use std::{env, fs, process, thread, time::Duration};
+use trace::{trace, FoldedSink};
+
+fn main() {
+    // Turn this file into a flamegraph with `inferno-flamegraph` or `flamegraph.pl`
+    let path = env::temp_dir().join(format!("example_flamegraph-{}.folded", process::id()));
+    trace::set_sink(FoldedSink::create(&path).unwrap());
+    // The file is complete as soon as the outermost traced call returns
+    run();
+
+    let folded = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    for line in folded.lines() {
+        let (stack, self_time) = line.rsplit_once(' ').unwrap();
+        assert!(self_time.parse::<u64>().unwrap() > 0);
+        println!("{}", stack);
+    }
+}
+
+#[trace]
+fn run() {
+    parse();
+    parse();
+    render();
+}
+
+#[trace]
+fn parse() {
+    lex();
+    thread::sleep(Duration::from_millis(1));
+}
+
+#[trace]
+fn lex() {
+    thread::sleep(Duration::from_millis(1));
+}
+
+#[trace]
+fn render() {
+    thread::sleep(Duration::from_millis(1));
+}
+
+#[cfg(test)]
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_flamegraph, main());
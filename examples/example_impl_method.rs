@@ -1,6 +1,6 @@
-use trace::trace;
+#![allow(clippy::disallowed_names)]
 
-trace::init_depth_var!();
+use trace::trace;
 
 fn main() {
     let foo = Foo;
@@ -0,0 +1,38 @@
+use std::thread;
+use trace::{trace, JsonSink};
+use trace_test::SharedBuffer;
+
+fn main() {
+    let buffer = SharedBuffer::default();
+    trace::set_sink(JsonSink::new(buffer.clone()));
+    thread::Builder::new()
+        .name("worker".to_owned())
+        .spawn(|| parse("1\n+2", '+'))
+        .unwrap()
+        .join()
+        .unwrap();
+    trace::reset_sink();
+
+    for line in buffer.contents().lines() {
+        let mut event: serde_json::Value = serde_json::from_str(line).unwrap();
+        let event = event.as_object_mut().unwrap();
+        assert!(event.remove("timestamp").unwrap().is_u64());
+        println!("{}", serde_json::to_string(event).unwrap());
+    }
+}
+
+#[trace(disable(separator))]
+fn parse(input: &str, separator: char) -> i32 {
+    input.split(separator).map(number).sum()
+}
+
+#[trace]
+fn number(n: &str) -> i32 {
+    n.trim().parse().unwrap()
+}
+
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_json, main());
@@ -0,0 +1,61 @@
+use parser::Describe;
+use trace::trace;
+
+fn main() {
+    let parser = parser::Parser::new("1 + 2");
+    lexer::new(parser.input());
+    parser.is_empty();
+    parser.describe();
+}
+
+mod parser {
+    use trace::trace;
+
+    #[derive(Debug)]
+    pub struct Parser {
+        input: String,
+    }
+
+    #[trace(location)]
+    impl Parser {
+        pub fn new(input: &str) -> Self {
+            Self {
+                input: input.to_string(),
+            }
+        }
+
+        pub fn input(&self) -> &str {
+            &self.input
+        }
+    }
+
+    impl Parser {
+        #[trace(location)]
+        pub fn is_empty(&self) -> bool {
+            self.input.is_empty()
+        }
+    }
+
+    #[trace(location)]
+    pub trait Describe {
+        fn describe(&self) -> String {
+            String::from("a parser")
+        }
+    }
+
+    impl Describe for Parser {}
+}
+
+#[trace(location)]
+mod lexer {
+    pub fn new(input: &str) -> usize {
+        input.len()
+    }
+}
+
+#[cfg(test)]
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_location, main());
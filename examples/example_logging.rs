@@ -3,8 +3,6 @@ extern crate log;
 
 use trace::trace;
 
-trace::init_depth_var!();
-
 fn main() {
     env_logger::init();
     foo(1, 2);
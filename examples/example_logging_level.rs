@@ -0,0 +1,39 @@
+use std::io::Write;
+use trace::trace;
+
+fn main() {
+    // Entering `parse` is logged at the debug level, while exiting it is logged at the trace level
+    // and filtered out like any other `log` record would be
+    env_logger::Builder::new()
+        .filter_level(log::LevelFilter::Debug)
+        .format(|buf, record| {
+            writeln!(
+                buf,
+                "{} {}: {}",
+                record.level(),
+                record.target(),
+                record.args()
+            )
+        })
+        .target(env_logger::Target::Stdout)
+        .init();
+
+    parse("1+2");
+}
+
+#[trace(logging = "debug", level_exit = "trace", target = "parser")]
+fn parse(input: &str) -> i32 {
+    input.split('+').map(number).sum()
+}
+
+#[trace(logging = "info", target = "parser")]
+fn number(n: &str) -> i32 {
+    n.parse().unwrap()
+}
+
+#[cfg(test)]
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_logging_level, main());
@@ -1,3 +1,5 @@
+#![allow(clippy::disallowed_names)]
+
 use trace::trace;
 
 fn main() {
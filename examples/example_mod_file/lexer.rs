@@ -0,0 +1,8 @@
+// Declared outside of the macro, as `lexer/token.rs` wraps its own items in `trace_module!`
+mod token;
+
+trace::trace_module! {
+    pub fn lex(input: &str) -> Vec<token::Token> {
+        input.split(' ').map(token::Token::new).collect()
+    }
+}
@@ -0,0 +1,14 @@
+trace::trace_module! {
+    #![trace(location)]
+
+    #[derive(Debug)]
+    pub struct Token {
+        pub len: usize,
+    }
+
+    impl Token {
+        pub fn new(s: &str) -> Self {
+            Self { len: s.len() }
+        }
+    }
+}
@@ -0,0 +1,17 @@
+// Each module file wraps its own items in `trace::trace_module!`, so their spans stay in that file,
+// as the location printed for `Token::new` shows
+mod lexer;
+
+fn main() {
+    let tokens = lexer::lex("1 22");
+    let lens = tokens.iter().map(|token| token.len).collect::<Vec<_>>();
+    println!("{:?}", lens);
+}
+
+#[cfg(test)]
+#[path = "../trace_test/mod.rs"]
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_mod_file, main());
@@ -6,6 +6,8 @@
 //    |    ^^^^^
 //    |
 //    = note: import resolution is stuck, try simplifying macro imports
+//
+// See `example_trace_module.rs` for tracing a whole crate on stable with `trace::trace_module!`.
 
 // #![feature(custom_inner_attributes)]
 // #![trace]
@@ -0,0 +1,40 @@
+use trace::trace;
+
+fn main() {
+    outer(1);
+}
+
+#[trace]
+fn outer(a: i32) -> i32 {
+    foo::foo(a) + Bar.bar(a)
+}
+
+#[trace]
+mod foo {
+    pub(super) fn foo(a: i32) -> i32 {
+        super::baz::baz(a) * 2
+    }
+}
+
+#[trace]
+mod baz {
+    pub(super) fn baz(a: i32) -> i32 {
+        a + 1
+    }
+}
+
+struct Bar;
+
+#[trace]
+impl Bar {
+    fn bar(&self, a: i32) -> i32 {
+        foo::foo(a)
+    }
+}
+
+#[cfg(test)]
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_multiple_mods, main());
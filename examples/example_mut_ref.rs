@@ -1,7 +1,5 @@
 use trace::trace;
 
-trace::init_depth_var!();
-
 fn main() {
     let mut a = 10;
     let mut b = 20;
@@ -0,0 +1,29 @@
+use std::panic;
+use trace::trace;
+
+fn main() {
+    panic::set_hook(Box::new(|_| {}));
+
+    let _ = panic::catch_unwind(|| check(-1));
+    check(1);
+}
+
+#[trace]
+fn check(a: i32) -> i32 {
+    if a < 0 {
+        fail("a is negative");
+    }
+    a
+}
+
+#[trace]
+fn fail(message: &str) -> ! {
+    panic!("{}", message)
+}
+
+#[cfg(test)]
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_never, main());
@@ -0,0 +1,25 @@
+use trace::trace;
+use trace_test::SharedBuffer;
+
+fn main() {
+    // Printed to stderr, so it doesn't end up in the output of the program
+    println!("{}", square(2));
+
+    let buffer = SharedBuffer::default();
+    trace::set_writer(buffer.clone());
+    println!("{}", square(3));
+    trace::reset_writer();
+
+    print!("{}", buffer.contents());
+}
+
+#[trace(output = "stderr")]
+fn square(x: i32) -> i32 {
+    x * x
+}
+
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_output, main());
@@ -0,0 +1,34 @@
+use std::panic;
+use trace::trace;
+
+fn main() {
+    // Silence the default panic message so that only the trace output is printed
+    panic::set_hook(Box::new(|_| {}));
+
+    let _ = panic::catch_unwind(|| bar(1));
+    let _ = panic::catch_unwind(|| bar(2));
+    bar(3);
+}
+
+#[trace]
+fn foo(a: i32) -> i32 {
+    if a == 1 {
+        panic!("a is {}", a);
+    }
+    a
+}
+
+#[trace]
+fn bar(a: i32) -> i32 {
+    if a == 2 {
+        panic::panic_any(a);
+    }
+    foo(a)
+}
+
+#[cfg(test)]
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_panic, main());
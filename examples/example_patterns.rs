@@ -0,0 +1,41 @@
+use trace::trace;
+
+fn main() {
+    let point = Point { x: 1, y: 2 };
+    distance(&point, Wrapper(3));
+    sum([4, 5, 6]);
+    unit(());
+    ignored(7, (8, 9));
+}
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+struct Wrapper(i32);
+
+#[trace]
+fn distance(&Point { x, y }: &Point, Wrapper(z): Wrapper) -> i32 {
+    x + y + z
+}
+
+#[trace]
+fn sum([a, .., c]: [u8; 3]) -> u8 {
+    a + c
+}
+
+#[trace]
+fn unit((): ()) {}
+
+#[trace]
+fn ignored(_: i32, (b, _): (i32, i32)) -> i32 {
+    b
+}
+
+#[cfg(test)]
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_patterns, main());
@@ -1,12 +1,11 @@
 use trace::trace;
 
-trace::init_depth_var!();
-
 fn main() {
     foo(Foo("Foo".to_string()));
 }
 
 #[derive(Debug)]
+#[allow(dead_code)]
 struct Foo(String);
 
 #[trace(pretty)]
@@ -0,0 +1,44 @@
+use trace::trace;
+
+fn main() {
+    let mut counter = Counter { count: 0 };
+    counter.incr(2);
+    counter.get();
+    let counter = counter.reset(3);
+    Box::new(counter).into_count();
+}
+
+#[derive(Debug)]
+struct Counter {
+    count: u32,
+}
+
+#[trace(show_self)]
+impl Counter {
+    fn incr(&mut self, by: u32) {
+        self.count += by;
+    }
+
+    fn get(&self) -> u32 {
+        self.count
+    }
+
+    fn into_count(self: Box<Self>) -> u32 {
+        self.count
+    }
+}
+
+impl Counter {
+    #[trace(enable(self, to))]
+    fn reset(mut self, to: u32) -> Self {
+        self.count = to;
+        self
+    }
+}
+
+#[cfg(test)]
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_show_self, main());
@@ -0,0 +1,70 @@
+use std::panic;
+use trace::{trace, EnterEvent, ExitEvent, PanicEvent, TraceSink};
+
+/// Prints every event on one line, the way a telemetry client would send it
+struct Telemetry;
+
+impl TraceSink for Telemetry {
+    fn on_enter(&self, event: &EnterEvent<'_>) {
+        let args = event
+            .args
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join(" ");
+        println!(
+            "enter {}::{} depth={} {}",
+            event.module_path, event.name, event.depth, args
+        );
+    }
+
+    fn on_exit(&self, event: &ExitEvent<'_>) {
+        println!(
+            "exit {}::{} depth={} return={}",
+            event.module_path,
+            event.name,
+            event.depth,
+            event.return_value.unwrap_or("?")
+        );
+    }
+
+    fn on_panic(&self, event: &PanicEvent<'_>) {
+        println!(
+            "panic {}::{} depth={} message={}",
+            event.module_path,
+            event.name,
+            event.depth,
+            event.message.unwrap_or("?")
+        );
+    }
+}
+
+fn main() {
+    // Silence the default panic message so that only the trace output is printed
+    panic::set_hook(Box::new(|_| {}));
+
+    trace::set_sink(Telemetry);
+    parse("1+2");
+    let _ = panic::catch_unwind(|| parse("1+x"));
+    trace::reset_sink();
+
+    // Printed as usual again
+    parse("3");
+}
+
+#[trace]
+fn parse(input: &str) -> i32 {
+    input.split('+').map(number).sum()
+}
+
+#[trace]
+fn number(n: &str) -> i32 {
+    n.parse().expect("not a number")
+}
+
+#[cfg(test)]
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_sink, main());
@@ -0,0 +1,64 @@
+use trace::trace;
+
+fn main() {
+    // In a real program, `let _report = trace::report_at_exit();` prints the report when `main`
+    // returns
+    fib(3);
+    greet("world");
+    greet("again");
+    Circle.name();
+    Square.name();
+    Square.name();
+
+    let mut report = Vec::new();
+    trace::write_report(&mut report).unwrap();
+
+    // The names of methods may contain spaces, so the six other columns are counted from the end
+    for line in String::from_utf8(report).unwrap().lines().skip(1) {
+        let columns = line.split_whitespace().collect::<Vec<_>>();
+        let (function, numbers) = columns.split_at(columns.len() - 6);
+        println!(
+            "{} calls={} max_depth={}",
+            function.join(" "),
+            numbers[0],
+            numbers[5]
+        );
+    }
+}
+
+#[trace(stats)]
+fn fib(n: u32) -> u32 {
+    if n < 2 {
+        n
+    } else {
+        fib(n - 1) + fib(n - 2)
+    }
+}
+
+#[trace(stats)]
+fn greet(name: &str) {
+    println!("Hello, {}!", name);
+}
+
+// Each implementation of the trait is counted on its own
+#[trace(stats)]
+trait Shape {
+    fn name(&self) -> &'static str {
+        "shape"
+    }
+}
+
+struct Circle;
+
+impl Shape for Circle {}
+
+struct Square;
+
+impl Shape for Square {}
+
+#[cfg(test)]
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_stats, main());
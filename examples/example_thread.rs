@@ -2,8 +2,6 @@ use std::thread;
 use std::time::Duration;
 use trace::trace;
 
-trace::init_depth_var!();
-
 fn main() {
     let handle = thread::spawn(|| {
         foo(10);
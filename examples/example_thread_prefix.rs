@@ -0,0 +1,44 @@
+use std::thread;
+use trace::trace;
+use trace_test::SharedBuffer;
+
+fn main() {
+    let buffer = SharedBuffer::default();
+    trace::set_writer(buffer.clone());
+    for name in ["worker-1", "worker-2"] {
+        thread::Builder::new()
+            .name(name.to_string())
+            .spawn(|| foo(1))
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+    trace::reset_writer();
+
+    for line in buffer.contents().lines() {
+        let (time, rest) = line.split_once(' ').unwrap();
+        assert!(time.parse::<f64>().is_ok());
+        println!("<time> {}", rest);
+    }
+}
+
+#[trace(thread, timestamp = "since_first_call")]
+fn foo(x: u32) -> u32 {
+    bar(x + 1)
+}
+
+#[trace(
+    thread,
+    timestamp = "since_first_call",
+    format_enter = "{x} on {thread}",
+    format_exit = "{r} on {thread}"
+)]
+fn bar(x: u32) -> u32 {
+    x * 2
+}
+
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_thread_prefix, main());
@@ -0,0 +1,36 @@
+use std::{thread, time::Duration};
+use trace::trace;
+use trace_test::SharedBuffer;
+
+fn main() {
+    let buffer = SharedBuffer::default();
+    trace::set_writer(buffer.clone());
+    slow(3);
+    trace::reset_writer();
+
+    for line in buffer.contents().lines() {
+        if line.ends_with("s)") {
+            let start = line.rfind(['(', ' ']).unwrap() + 1;
+            println!("{}<elapsed>)", &line[..start]);
+        } else {
+            println!("{}", line);
+        }
+    }
+}
+
+#[trace(timing)]
+fn slow(n: u64) -> u64 {
+    thread::sleep(Duration::from_millis(n));
+    fast(n) * 2
+}
+
+#[trace(timing, format_exit = "{r} (took {elapsed})")]
+fn fast(n: u64) -> u64 {
+    n + 1
+}
+
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_timing, main());
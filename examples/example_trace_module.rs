@@ -0,0 +1,22 @@
+// Declared outside of the macro, as the module's file wraps its own items in `trace_module!`
+mod example_trace_module_parser;
+
+trace::trace_module! {
+    #![trace(disable(main))]
+
+    fn main() {
+        let numbers = example_trace_module_parser::parse("1 2");
+        sum(&numbers);
+    }
+
+    fn sum(numbers: &[u32]) -> u32 {
+        numbers.iter().sum()
+    }
+}
+
+#[cfg(test)]
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_trace_module, main());
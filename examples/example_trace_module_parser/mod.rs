@@ -0,0 +1,11 @@
+//! A module in its own file, which is traced by wrapping its items in `trace_module!` as well
+
+trace::trace_module! {
+    pub(super) fn parse(input: &str) -> Vec<u32> {
+        input.split_whitespace().map(parse_number).collect()
+    }
+
+    fn parse_number(token: &str) -> u32 {
+        token.parse().unwrap()
+    }
+}
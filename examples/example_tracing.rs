@@ -0,0 +1,48 @@
+use trace::trace;
+use tracing_subscriber::fmt::format::FmtSpan;
+
+fn main() {
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::TRACE)
+        .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE)
+        .with_ansi(false)
+        .without_time()
+        .with_writer(std::io::stdout)
+        .init();
+
+    Parser::new("1+2").parse();
+    async_std::task::block_on(fetch(7));
+}
+
+#[trace(tracing = "debug", target = "fetcher")]
+async fn fetch(id: u32) -> String {
+    async_std::task::yield_now().await;
+    format!("item {}", id)
+}
+
+#[derive(Debug)]
+struct Parser<'a> {
+    input: &'a str,
+}
+
+#[trace(tracing, show_self)]
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input }
+    }
+
+    fn parse(&self) -> i32 {
+        self.input.split('+').map(|n| self.number(n)).sum()
+    }
+
+    fn number(&self, n: &str) -> i32 {
+        n.parse().unwrap()
+    }
+}
+
+#[cfg(test)]
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_tracing, main());
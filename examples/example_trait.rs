@@ -0,0 +1,53 @@
+use trace::trace;
+
+fn main() {
+    let circle = Circle { radius: 2 };
+    circle.describe();
+    circle.scaled_area(3);
+    circle.perimeter();
+    circle.name();
+}
+
+#[trace(disable(perimeter))]
+trait Shape {
+    fn area(&self) -> u32;
+
+    fn describe(&self) -> String {
+        format!("shape with area {}", self.area())
+    }
+
+    fn scaled_area(&self, factor: u32) -> u32 {
+        self.area() * factor
+    }
+
+    fn perimeter(&self) -> u32 {
+        0
+    }
+}
+
+trait Named {
+    #[trace]
+    fn name(&self) -> &'static str {
+        "circle"
+    }
+}
+
+struct Circle {
+    radius: u32,
+}
+
+#[trace]
+impl Shape for Circle {
+    fn area(&self) -> u32 {
+        3 * self.radius * self.radius
+    }
+}
+
+impl Named for Circle {}
+
+#[cfg(test)]
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_trait, main());
@@ -0,0 +1,65 @@
+use std::fmt::Debug;
+use trace::trace;
+
+fn main() {
+    let mut stack = Stack::new();
+    stack.push(1);
+    let stack = Stack::from(vec![2, 3]);
+    stack.as_ref();
+    Stack::<u8>::default();
+    Buffer.len();
+}
+
+#[derive(Debug)]
+struct Stack<T> {
+    values: Vec<T>,
+}
+
+#[trace(type_names)]
+impl<T: Debug> Stack<T> {
+    fn new() -> Self {
+        Self { values: vec![] }
+    }
+
+    fn push(&mut self, value: T) {
+        self.values.push(value);
+    }
+}
+
+#[trace(type_names)]
+impl<T: Debug> From<Vec<T>> for Stack<T> {
+    fn from(values: Vec<T>) -> Self {
+        Self { values }
+    }
+}
+
+#[trace]
+impl<T: Debug> AsRef<[T]> for Stack<T> {
+    fn as_ref(&self) -> &[T] {
+        &self.values
+    }
+}
+
+#[trace(location)]
+impl<T: Debug> Default for Stack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct Buffer<const N: usize>;
+
+// The name contains braces, which are printed like any other character
+#[trace(type_names)]
+impl Buffer<{ 1 + 2 }> {
+    fn len(&self) -> usize {
+        3
+    }
+}
+
+#[cfg(test)]
+#[macro_use]
+mod trace_test;
+
+#[cfg(test)]
+trace_test!(test_type_names, main());
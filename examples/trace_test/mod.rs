@@ -1,3 +1,8 @@
+use std::{
+    io::{self, Write},
+    sync::{Arc, Mutex},
+};
+
 #[macro_export]
 macro_rules! trace_test {
     ($test_name:ident, $expression:expr) => {
@@ -22,3 +27,27 @@ macro_rules! trace_test {
         }
     };
 }
+
+/// A buffer that can still be read after it's registered with `trace::set_writer` or given to a
+/// sink, for the examples whose output has to be processed before it's printed
+#[allow(dead_code)]
+#[derive(Clone, Default)]
+pub struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+#[allow(dead_code)]
+impl SharedBuffer {
+    /// Returns everything written to the buffer so far
+    pub fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
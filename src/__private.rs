@@ -0,0 +1,203 @@
+//! Runtime support for the code generated by `#[trace]`, which isn't part of the public API
+
+use std::{
+    any::Any,
+    cell::Cell,
+    fmt,
+    future::Future,
+    io::{self, BufRead},
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::OnceLock,
+    task::{Context, Poll},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+pub use crate::__trace_if_tracing as if_tracing;
+pub use crate::__trace_println as println;
+pub use crate::enabled::is_enabled;
+pub use crate::output::{write_line, Output};
+pub use crate::sink::{on_enter, on_exit, on_panic, sink, Callsite};
+pub use crate::stats::{stats_enter, stats_exit};
+pub use log;
+#[cfg(feature = "tracing")]
+pub use tracing;
+
+/// Expands to the code generated by `#[trace(tracing)]`, which needs the `tracing` feature
+#[cfg(feature = "tracing")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __trace_if_tracing {
+    ($($body:tt)*) => {
+        $($body)*
+    };
+}
+
+/// Reports that `#[trace(tracing)]` is used without the `tracing` feature, at the attribute
+#[cfg(not(feature = "tracing"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __trace_if_tracing {
+    ($($body:tt)*) => {
+        ::std::compile_error!(
+            "`#[trace(tracing)]` requires the `tracing` feature of `trace`, enable it in Cargo.toml"
+        )
+    };
+}
+
+std::thread_local! {
+    /// The indentation depth of the tracing output, shared by all traced functions
+    pub static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Waits for enter to be pressed
+pub fn pause() {
+    io::stdin().lock().lines().next();
+}
+
+/// Runs the body of a traced function, catching any panic so that it can be reported
+///
+/// `f` is bound by `FnOnce` so that the closure it's called with may return borrows of the
+/// arguments it captures.
+pub fn catch_unwind<R, F: FnOnce() -> R>(f: F) -> thread::Result<R> {
+    panic::catch_unwind(AssertUnwindSafe(f))
+}
+
+/// Returns the message of a panic, if its payload is a `&str` or a `String`
+pub fn panic_message(payload: &(dyn Any + Send)) -> Option<&str> {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+}
+
+/// The duration of a traced call, as it's printed with `timing`
+pub struct Elapsed(pub Duration);
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nanos = self.0.as_nanos();
+        if nanos < 1_000 {
+            write!(f, "{}ns", nanos)
+        } else if nanos < 1_000_000 {
+            write!(f, "{:.2}µs", nanos as f64 / 1e3)
+        } else if nanos < 1_000_000_000 {
+            write!(f, "{:.2}ms", nanos as f64 / 1e6)
+        } else {
+            write!(f, "{:.2}s", self.0.as_secs_f64())
+        }
+    }
+}
+
+/// The name of the current thread, or its id if it doesn't have a name
+pub struct CurrentThread;
+
+impl fmt::Display for CurrentThread {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let thread = thread::current();
+        match thread.name() {
+            Some(name) => f.write_str(name),
+            None => write!(f, "{:?}", thread.id()),
+        }
+    }
+}
+
+/// The current time, as it's printed with `timestamp` or interpolated with `{time}`
+pub enum Timestamp {
+    /// The time of day in UTC, like `14:03:27.123456`
+    Wall,
+    /// The seconds since the first call of a traced function, like `2.000123`
+    SinceFirstCall,
+}
+
+/// When a traced function was first called, which `Timestamp::SinceFirstCall` counts from
+static FIRST_CALL: OnceLock<Instant> = OnceLock::new();
+
+/// Starts the clock of `Timestamp::SinceFirstCall` unless it's already running
+pub(crate) fn start_clock() {
+    FIRST_CALL.get_or_init(Instant::now);
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Timestamp::Wall => {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default();
+                let seconds = now.as_secs() % (24 * 60 * 60);
+                write!(
+                    f,
+                    "{:02}:{:02}:{:02}.{:06}",
+                    seconds / (60 * 60),
+                    seconds / 60 % 60,
+                    seconds % 60,
+                    now.subsec_micros()
+                )
+            }
+            Timestamp::SinceFirstCall => {
+                let elapsed = FIRST_CALL.get_or_init(Instant::now).elapsed();
+                write!(f, "{}.{:06}", elapsed.as_secs(), elapsed.subsec_micros())
+            }
+        }
+    }
+}
+
+pub enum PollEvent {
+    /// The future returned `Poll::Pending`
+    Suspended,
+    /// The future is polled again after being suspended
+    Resumed,
+}
+
+/// Wraps the body of a traced `async fn`
+///
+/// The body keeps its own depth, which is swapped into `DEPTH` for the duration of each poll.
+/// Otherwise futures polled concurrently on the same thread would all share (and corrupt) the same
+/// depth across their `.await` points. Panics are caught like with [`catch_unwind`].
+pub struct TracedFuture<F, E> {
+    inner: F,
+    depth: usize,
+    polled: bool,
+    on_poll_event: E,
+}
+
+impl<F, E> TracedFuture<F, E> {
+    pub fn new(inner: F, depth: usize, on_poll_event: E) -> Self {
+        Self {
+            inner,
+            depth,
+            polled: false,
+            on_poll_event,
+        }
+    }
+}
+
+impl<F: Future, E: FnMut(PollEvent) + Unpin> Future for TracedFuture<F, E> {
+    type Output = thread::Result<F::Output>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `inner` is pinned along with the `TracedFuture`, it's never moved out of it and
+        // there's no `Drop` impl that could move it. The other fields aren't pinned.
+        let this = unsafe { self.get_unchecked_mut() };
+        let mut inner = unsafe { Pin::new_unchecked(&mut this.inner) };
+        if this.polled {
+            (this.on_poll_event)(PollEvent::Resumed);
+        }
+        this.polled = true;
+
+        let outer_depth = DEPTH.with(|d| d.replace(this.depth));
+        let poll = panic::catch_unwind(AssertUnwindSafe(|| inner.as_mut().poll(cx)));
+        this.depth = DEPTH.with(|d| d.replace(outer_depth));
+
+        match poll {
+            Ok(Poll::Pending) => {
+                (this.on_poll_event)(PollEvent::Suspended);
+                Poll::Pending
+            }
+            Ok(Poll::Ready(output)) => Poll::Ready(Ok(output)),
+            Err(payload) => Poll::Ready(Err(payload)),
+        }
+    }
+}
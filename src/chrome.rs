@@ -0,0 +1,178 @@
+//! Writing trace events in the Trace Event Format of `chrome://tracing` and Perfetto
+
+use std::{
+    cell::Cell,
+    fmt::Write as _,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    process,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, PoisonError,
+    },
+    time::Instant,
+};
+
+use crate::{
+    __private::CurrentThread,
+    json::{push_json_opt_str, push_json_str},
+    sink::{EnterEvent, ExitEvent, PanicEvent, TraceSink},
+};
+
+/// The ids given to threads in the trace, which are shared by all sinks so that they stay the same
+/// when the sink is replaced
+static NEXT_TID: AtomicU64 = AtomicU64::new(1);
+
+std::thread_local! {
+    /// The id of the current thread in the trace, `0` until it's assigned
+    static TID: Cell<u64> = const { Cell::new(0) };
+}
+
+/// A [`TraceSink`] that writes a JSON trace which can be opened in `chrome://tracing` or
+/// [Perfetto](https://ui.perfetto.dev)
+///
+/// Every traced call is a pair of `"B"` and `"E"` records, with timestamps in microseconds since
+/// the sink was created. The arguments are the `args` of the `"B"` record, and the return value (or
+/// the panic message) those of the `"E"` record. Each thread is named after the current thread the
+/// first time it writes a record.
+///
+/// The records are buffered, and flushed every time a call that isn't nested in another traced call
+/// on the same thread ends. The JSON array is only closed when the sink is dropped, so install it
+/// with [`sink_scope`](crate::sink_scope) and keep the guard until the end of the run, or remove it
+/// with [`reset_sink`](crate::reset_sink) and drop it. A sink installed with
+/// [`set_sink`](crate::set_sink) that's never removed is never dropped, which leaves the array
+/// open. The viewers accept such traces, but JSON parsers don't.
+pub struct ChromeSink {
+    start: Instant,
+    pid: u32,
+    state: Mutex<State>,
+}
+
+struct State {
+    writer: Box<dyn Write + Send>,
+    /// Whether no record was written yet, as the others are preceded by a comma
+    empty: bool,
+    /// The threads for which a `thread_name` record was written
+    named_tids: Vec<u64>,
+}
+
+impl ChromeSink {
+    /// Writes the trace to a new file at `path`, replacing it if it exists
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        File::create(path).and_then(|file| Self::new(BufWriter::new(file)))
+    }
+
+    /// Writes the trace to `writer`, which should be buffered
+    pub fn new<W: Write + Send + 'static>(mut writer: W) -> io::Result<Self> {
+        writer.write_all(b"[\n")?;
+
+        Ok(Self {
+            start: Instant::now(),
+            pid: process::id(),
+            state: Mutex::new(State {
+                writer: Box::new(writer),
+                empty: true,
+                named_tids: Vec::new(),
+            }),
+        })
+    }
+
+    /// Writes a record, which `extra_fields` completes
+    fn write_record(
+        &self,
+        ph: &str,
+        name: &str,
+        module_path: &str,
+        depth: usize,
+        extra_fields: &str,
+    ) {
+        let ts = self.start.elapsed().as_secs_f64() * 1_000_000.0;
+        let tid = TID.with(|tid| {
+            if tid.get() == 0 {
+                tid.set(NEXT_TID.fetch_add(1, Ordering::Relaxed));
+            }
+            tid.get()
+        });
+
+        let mut record = String::new();
+        record.push_str("{\"name\":");
+        push_json_str(&mut record, name);
+        record.push_str(",\"cat\":");
+        push_json_str(&mut record, module_path);
+        let _ = write!(
+            record,
+            ",\"ph\":\"{}\",\"ts\":{:.3},\"pid\":{},\"tid\":{}{}}}",
+            ph, ts, self.pid, tid, extra_fields
+        );
+
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if !state.named_tids.contains(&tid) {
+            state.named_tids.push(tid);
+
+            let thread = CurrentThread.to_string();
+            let mut metadata = format!(
+                "{{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":{},\"tid\":{},\"args\":{{\"name\":",
+                self.pid, tid
+            );
+            push_json_str(&mut metadata, &thread);
+            metadata.push_str("}}");
+            state.write(&metadata);
+        }
+        state.write(&record);
+        if ph == "E" && depth == 0 {
+            let _ = state.writer.flush();
+        }
+    }
+}
+
+impl State {
+    fn write(&mut self, record: &str) {
+        let separator = if self.empty { "" } else { ",\n" };
+        self.empty = false;
+        let _ = self
+            .writer
+            .write_all(separator.as_bytes())
+            .and_then(|()| self.writer.write_all(record.as_bytes()));
+    }
+}
+
+impl TraceSink for ChromeSink {
+    fn on_enter(&self, event: &EnterEvent<'_>) {
+        let mut args = String::from(",\"args\":{");
+        for (i, (name, value)) in event.args.iter().enumerate() {
+            if i > 0 {
+                args.push(',');
+            }
+            push_json_str(&mut args, name);
+            args.push(':');
+            push_json_str(&mut args, value);
+        }
+        args.push('}');
+        self.write_record("B", event.name, event.module_path, event.depth, &args);
+    }
+
+    fn on_exit(&self, event: &ExitEvent<'_>) {
+        let mut args = String::from(",\"args\":{\"return_value\":");
+        push_json_opt_str(&mut args, event.return_value);
+        args.push('}');
+        self.write_record("E", event.name, event.module_path, event.depth, &args);
+    }
+
+    fn on_panic(&self, event: &PanicEvent<'_>) {
+        let mut args = String::from(",\"args\":{\"panic\":");
+        push_json_opt_str(&mut args, event.message);
+        args.push('}');
+        self.write_record("E", event.name, event.module_path, event.depth, &args);
+    }
+}
+
+impl Drop for ChromeSink {
+    fn drop(&mut self) {
+        let state = self.state.get_mut().unwrap_or_else(PoisonError::into_inner);
+        let _ = state
+            .writer
+            .write_all(b"\n]\n")
+            .and_then(|()| state.writer.flush());
+    }
+}
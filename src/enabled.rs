@@ -0,0 +1,93 @@
+//! Turning tracing on and off at runtime
+
+use std::{
+    cell::Cell,
+    marker::PhantomData,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Whether traced calls are reported on threads that don't override it
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+std::thread_local! {
+    /// Whether traced calls are reported on the current thread, if it overrides [`ENABLED`]
+    static THREAD_ENABLED: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+/// Turns the tracing of every thread on or off, which is on by default
+///
+/// While tracing is off, traced functions don't print, log or report anything to a
+/// [`TraceSink`](crate::TraceSink), and their arguments and return values aren't formatted. The
+/// threads that called [`set_thread_enabled`] keep their own setting.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Turns the tracing of the current thread on or off, regardless of [`set_enabled`]
+pub fn set_thread_enabled(enabled: bool) {
+    THREAD_ENABLED.with(|thread_enabled| thread_enabled.set(Some(enabled)));
+}
+
+/// Makes the current thread follow [`set_enabled`] again after [`set_thread_enabled`]
+pub fn reset_thread_enabled() {
+    THREAD_ENABLED.with(|thread_enabled| thread_enabled.set(None));
+}
+
+/// Returns whether traced calls are reported on the current thread
+pub fn is_enabled() -> bool {
+    THREAD_ENABLED
+        .with(Cell::get)
+        .unwrap_or_else(|| ENABLED.load(Ordering::Relaxed))
+}
+
+/// Turns the tracing of every thread on until the returned guard is dropped, see [`set_enabled`]
+///
+/// ```
+/// trace::set_enabled(false);
+/// // Not traced
+/// {
+///     let _guard = trace::enabled_scope();
+///     // Traced
+/// }
+/// // Not traced again
+/// ```
+#[must_use = "tracing is turned off again as soon as the guard is dropped"]
+pub fn enabled_scope() -> EnabledGuard {
+    EnabledGuard {
+        previous: ENABLED.swap(true, Ordering::Relaxed),
+    }
+}
+
+/// Turns the tracing of the current thread on until the returned guard is dropped, see
+/// [`set_thread_enabled`]
+#[must_use = "tracing is turned off again as soon as the guard is dropped"]
+pub fn thread_enabled_scope() -> ThreadEnabledGuard {
+    ThreadEnabledGuard {
+        previous: THREAD_ENABLED.with(|thread_enabled| thread_enabled.replace(Some(true))),
+        _not_send: PhantomData,
+    }
+}
+
+/// Restores whether every thread is traced when it's dropped, see [`enabled_scope`]
+pub struct EnabledGuard {
+    previous: bool,
+}
+
+impl Drop for EnabledGuard {
+    fn drop(&mut self) {
+        ENABLED.store(self.previous, Ordering::Relaxed);
+    }
+}
+
+/// Restores whether the current thread is traced when it's dropped, see [`thread_enabled_scope`]
+pub struct ThreadEnabledGuard {
+    previous: Option<bool>,
+    /// The guard has to be dropped on the thread it was created on
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for ThreadEnabledGuard {
+    fn drop(&mut self) {
+        THREAD_ENABLED.with(|thread_enabled| thread_enabled.set(self.previous));
+    }
+}
@@ -0,0 +1,115 @@
+//! Choosing the traced functions at runtime with the `RUST_TRACE` environment variable
+
+use std::{env, sync::OnceLock};
+
+/// The environment variable that the filter is read from
+const FILTER_VAR: &str = "RUST_TRACE";
+
+/// The filter read from [`FILTER_VAR`] the first time a traced function is called
+static FILTER: OnceLock<Filter> = OnceLock::new();
+
+/// A comma-separated list of patterns of functions to trace, or not to trace with a leading `!`
+///
+/// The patterns are matched against the path of functions, e.g. `my_crate::parser::lex_char`, and
+/// may leave out any number of leading modules, so `parser::lex_char` matches that function as
+/// well. A `*` matches any number of characters, including `::`, so `parser::*` matches every
+/// function in `parser` and its submodules.
+///
+/// The methods of a traced `impl` of a trait are matched both as `<Type as Trait>::name` and as
+/// `Type::name`, so `Logger::log` matches `<Logger as Log>::log` as well. The methods that
+/// `#[trace]` is applied to directly and those of a traced `trait` are only matched by their module
+/// and name, as their `Self` type isn't known when the filter is applied.
+///
+/// If there are patterns without a `!`, only the functions matching one of them are traced. The
+/// functions matching a pattern with a `!` are never traced.
+struct Filter {
+    enabled: Vec<String>,
+    disabled: Vec<String>,
+}
+
+impl Filter {
+    fn from_env() -> Self {
+        let mut filter = Filter {
+            enabled: vec![],
+            disabled: vec![],
+        };
+
+        for directive in env::var(FILTER_VAR).unwrap_or_default().split(',') {
+            let directive = directive.trim();
+            if let Some(pattern) = directive.strip_prefix('!') {
+                filter.disabled.push(pattern.trim().to_owned());
+            } else if !directive.is_empty() {
+                filter.enabled.push(directive.to_owned());
+            }
+        }
+
+        filter
+    }
+
+    fn is_traced(&self, paths: &[String]) -> bool {
+        let matches = |pattern: &String| paths.iter().any(|path| matches_path(pattern, path));
+        (self.enabled.is_empty() || self.enabled.iter().any(matches))
+            && !self.disabled.iter().any(matches)
+    }
+}
+
+/// Returns whether `pattern` matches `path`, or the end of `path` after a `::`
+fn matches_path(pattern: &str, path: &str) -> bool {
+    let mut suffix = path;
+    loop {
+        if matches_glob(pattern.as_bytes(), suffix.as_bytes()) {
+            return true;
+        }
+        match suffix.find("::") {
+            Some(i) => suffix = &suffix[i + 2..],
+            None => return false,
+        }
+    }
+}
+
+/// Returns whether `pattern` matches all of `text`, where a `*` in `pattern` matches any number of
+/// characters
+fn matches_glob(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|i| matches_glob(rest, &text[i..])),
+        Some((c, rest)) => text.first() == Some(c) && matches_glob(rest, &text[1..]),
+    }
+}
+
+/// Returns `name` without the trait if it's written like `<Type as Trait>::name`
+fn without_trait(name: &str) -> Option<String> {
+    let qualified = name.strip_prefix('<')?;
+    // The ` as ` and `>` that end the type are the first ones outside of generic arguments, where
+    // the `>` of an `->` doesn't count
+    let mut depth = 0;
+    let mut as_index = None;
+    let mut previous = ' ';
+    for (i, c) in qualified.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' if previous == '-' => (),
+            '>' if depth > 0 => depth -= 1,
+            '>' => {
+                let name = qualified[i + 1..].strip_prefix("::")?;
+                return Some(format!("{}::{}", &qualified[..as_index?], name));
+            }
+            ' ' if depth == 0 && as_index.is_none() && qualified[i..].starts_with(" as ") => {
+                as_index = Some(i);
+            }
+            _ => (),
+        }
+        previous = c;
+    }
+
+    None
+}
+
+/// Returns whether a function is traced according to `RUST_TRACE`
+pub(crate) fn is_traced(module_path: &str, name: &str) -> bool {
+    let paths = std::iter::once(name.to_owned())
+        .chain(without_trait(name))
+        .map(|name| format!("{}::{}", module_path, name))
+        .collect::<Vec<_>>();
+    FILTER.get_or_init(Filter::from_env).is_traced(&paths)
+}
@@ -0,0 +1,138 @@
+//! Collecting the time spent in traced functions as folded stacks for flamegraphs
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write as _,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+    thread::{self, ThreadId},
+    time::{Duration, Instant},
+};
+
+use crate::sink::{EnterEvent, ExitEvent, PanicEvent, TraceSink};
+
+/// A [`TraceSink`] that writes the time spent in traced functions as folded stacks, which
+/// [`inferno`](https://github.com/jonhoo/inferno) and `flamegraph.pl` turn into flamegraphs
+///
+/// Each line of the file is a stack of traced calls followed by the time spent in the innermost
+/// one, excluding the traced calls it made, in nanoseconds, e.g. `main;foo;bar 1200`. Calls with
+/// the same stack are added up.
+///
+/// The stacks are collected in memory, and the file is written again every time a call that isn't
+/// nested in another traced call on the same thread returns, so it's complete once the outermost
+/// traced function returns (e.g. `main`), even if the sink is installed with
+/// [`set_sink`](crate::set_sink) and never dropped. It's also written when the sink is dropped.
+/// `async fn`s that are moved to another thread while suspended aren't collected correctly.
+pub struct FoldedSink {
+    path: PathBuf,
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    /// The calls that are being executed on each thread
+    stacks: HashMap<ThreadId, Vec<Frame>>,
+    /// The time spent in each stack, excluding the traced calls made from it
+    self_times: BTreeMap<String, Duration>,
+}
+
+struct Frame {
+    name: &'static str,
+    depth: usize,
+    start: Instant,
+    /// The time spent in the traced calls made from this one
+    children: Duration,
+}
+
+impl FoldedSink {
+    /// Writes the folded stacks to a file at `path`, which is created (or emptied) right away
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        fs::write(&path, "")?;
+
+        Ok(Self {
+            path: path.as_ref().to_owned(),
+            state: Mutex::new(State::default()),
+        })
+    }
+
+    fn push(&self, name: &'static str, depth: usize) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state
+            .stacks
+            .entry(thread::current().id())
+            .or_default()
+            .push(Frame {
+                name,
+                depth,
+                start: Instant::now(),
+                children: Duration::ZERO,
+            });
+    }
+
+    fn pop(&self, name: &'static str, depth: usize) {
+        let end = Instant::now();
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let state = &mut *state;
+        let Some(stack) = state.stacks.get_mut(&thread::current().id()) else {
+            return;
+        };
+        // Skip the calls whose exit wasn't reported, e.g. because they were entered with a
+        // different sink
+        let Some(index) = stack
+            .iter()
+            .rposition(|frame| frame.name == name && frame.depth == depth)
+        else {
+            return;
+        };
+        stack.truncate(index + 1);
+
+        let folded = stack
+            .iter()
+            .map(|frame| frame.name)
+            .collect::<Vec<_>>()
+            .join(";");
+        let frame = stack.pop().unwrap();
+        let elapsed = end - frame.start;
+        *state.self_times.entry(folded).or_default() += elapsed.saturating_sub(frame.children);
+
+        match stack.last_mut() {
+            Some(parent) => parent.children += elapsed,
+            None if depth == 0 => state.write(&self.path),
+            None => (),
+        }
+    }
+}
+
+impl State {
+    fn write(&self, path: &Path) {
+        let mut folded = String::new();
+        for (stack, self_time) in &self.self_times {
+            let _ = writeln!(folded, "{} {}", stack, self_time.as_nanos());
+        }
+        let _ = fs::write(path, folded);
+    }
+}
+
+impl TraceSink for FoldedSink {
+    fn on_enter(&self, event: &EnterEvent<'_>) {
+        self.push(event.name, event.depth);
+    }
+
+    fn on_exit(&self, event: &ExitEvent<'_>) {
+        self.pop(event.name, event.depth);
+    }
+
+    fn on_panic(&self, event: &PanicEvent<'_>) {
+        self.pop(event.name, event.depth);
+    }
+}
+
+impl Drop for FoldedSink {
+    fn drop(&mut self) {
+        self.state
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .write(&self.path);
+    }
+}
@@ -0,0 +1,177 @@
+//! Writing trace events as JSON Lines
+
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+    sync::{Mutex, PoisonError},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    __private::CurrentThread,
+    sink::{EnterEvent, ExitEvent, PanicEvent, TraceSink},
+};
+
+/// A [`TraceSink`] that writes every event as a JSON object on its own line
+///
+/// Each object has the following fields:
+///
+/// - `event` - `"enter"`, `"exit"` or `"panic"`
+/// - `function` - The name of the traced function
+/// - `module_path` - The path of the module containing the function
+/// - `file` and `line` - Where the function is defined
+/// - `thread` - The name of the current thread, or its id if it doesn't have a name
+/// - `depth` - The number of traced calls the function was called from
+/// - `timestamp` - The number of microseconds since the Unix epoch
+/// - `args` - For `"enter"` events, an object mapping the names of the arguments to their `Debug`
+///   representation
+/// - `return_value` - For `"exit"` events, the `Debug` representation of the returned value, or
+///   `null` if it's left out by `format_exit`
+/// - `elapsed_ns` - For `"exit"` events, the number of nanoseconds the call took, or `null` unless
+///   it's measured with `timing`
+/// - `message` - For `"panic"` events, the message of the panic, or `null` if its payload isn't a
+///   `&str` or a `String`
+///
+/// Every line is flushed as soon as it's written. Errors while writing are ignored.
+pub struct JsonSink {
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl JsonSink {
+    pub fn new<W: Write + Send + 'static>(writer: W) -> Self {
+        Self {
+            writer: Mutex::new(Box::new(writer)),
+        }
+    }
+
+    /// Writes the events to stdout
+    pub fn stdout() -> Self {
+        Self::new(io::stdout())
+    }
+
+    /// Writes the fields that all events have, leaving the object open for the rest
+    fn start_event(
+        json: &mut String,
+        event: &str,
+        name: &str,
+        module_path: &str,
+        file: &str,
+        line: u32,
+        depth: usize,
+    ) {
+        let thread = CurrentThread.to_string();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_micros());
+
+        json.push_str("{\"event\":");
+        push_json_str(json, event);
+        json.push_str(",\"function\":");
+        push_json_str(json, name);
+        json.push_str(",\"module_path\":");
+        push_json_str(json, module_path);
+        json.push_str(",\"file\":");
+        push_json_str(json, file);
+        let _ = write!(json, ",\"line\":{},\"thread\":", line);
+        push_json_str(json, &thread);
+        let _ = write!(json, ",\"depth\":{},\"timestamp\":{}", depth, timestamp);
+    }
+
+    fn write_line(&self, mut json: String) {
+        json.push_str("}\n");
+        let mut writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let _ = writer
+            .write_all(json.as_bytes())
+            .and_then(|()| writer.flush());
+    }
+}
+
+impl TraceSink for JsonSink {
+    fn on_enter(&self, event: &EnterEvent<'_>) {
+        let mut json = String::new();
+        Self::start_event(
+            &mut json,
+            "enter",
+            event.name,
+            event.module_path,
+            event.file,
+            event.line,
+            event.depth,
+        );
+        json.push_str(",\"args\":{");
+        for (i, (name, value)) in event.args.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            push_json_str(&mut json, name);
+            json.push(':');
+            push_json_str(&mut json, value);
+        }
+        json.push('}');
+        self.write_line(json);
+    }
+
+    fn on_exit(&self, event: &ExitEvent<'_>) {
+        let mut json = String::new();
+        Self::start_event(
+            &mut json,
+            "exit",
+            event.name,
+            event.module_path,
+            event.file,
+            event.line,
+            event.depth,
+        );
+        json.push_str(",\"return_value\":");
+        push_json_opt_str(&mut json, event.return_value);
+        match event.elapsed {
+            Some(elapsed) => {
+                let _ = write!(json, ",\"elapsed_ns\":{}", elapsed.as_nanos());
+            }
+            None => json.push_str(",\"elapsed_ns\":null"),
+        }
+        self.write_line(json);
+    }
+
+    fn on_panic(&self, event: &PanicEvent<'_>) {
+        let mut json = String::new();
+        Self::start_event(
+            &mut json,
+            "panic",
+            event.name,
+            event.module_path,
+            event.file,
+            event.line,
+            event.depth,
+        );
+        json.push_str(",\"message\":");
+        push_json_opt_str(&mut json, event.message);
+        self.write_line(json);
+    }
+}
+
+pub(crate) fn push_json_opt_str(json: &mut String, s: Option<&str>) {
+    match s {
+        Some(s) => push_json_str(json, s),
+        None => json.push_str("null"),
+    }
+}
+
+/// Pushes `s` as a JSON string, escaping it as needed
+pub(crate) fn push_json_str(json: &mut String, s: &str) {
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
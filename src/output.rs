@@ -0,0 +1,64 @@
+//! Where the lines printed by `#[trace]` go when neither `logging` nor `tracing` is used
+
+use std::{
+    fmt,
+    io::Write,
+    sync::{Mutex, PoisonError},
+};
+
+/// The writer registered with [`set_writer`], which takes precedence over `output`
+static WRITER: Mutex<Option<Box<dyn Write + Send>>> = Mutex::new(None);
+
+/// Sends the output of every `#[trace]` expansion that doesn't use `logging` or `tracing` to
+/// `writer`, e.g. a file or an in-memory buffer, instead of stdout or stderr
+///
+/// This applies to the whole process, regardless of the `output` argument of `#[trace]`. Every line
+/// is flushed as soon as it's written, since the writer may never be dropped. Errors while writing
+/// are ignored.
+pub fn set_writer<W: Write + Send + 'static>(writer: W) {
+    *WRITER.lock().unwrap_or_else(PoisonError::into_inner) = Some(Box::new(writer));
+}
+
+/// Removes the writer registered with [`set_writer`], returning it
+///
+/// The output of `#[trace]` goes back to stdout, or stderr with `output = "stderr"`.
+pub fn reset_writer() -> Option<Box<dyn Write + Send>> {
+    WRITER.lock().unwrap_or_else(PoisonError::into_inner).take()
+}
+
+#[doc(hidden)]
+pub enum Output {
+    Stdout,
+    Stderr,
+}
+
+#[doc(hidden)]
+pub fn write_line(output: Output, args: fmt::Arguments<'_>) {
+    // Formatted before taking the lock, as a `Debug` implementation may call traced functions too
+    let line = fmt::format(args);
+    let mut writer = WRITER.lock().unwrap_or_else(PoisonError::into_inner);
+    match *writer {
+        Some(ref mut writer) => {
+            let _ = writeln!(writer, "{}", line).and_then(|()| writer.flush());
+        }
+        // `println!` and `eprintln!` rather than writing to `io::stdout()` directly, as only they
+        // are captured by the test harness
+        None => match output {
+            Output::Stdout => println!("{}", line),
+            Output::Stderr => eprintln!("{}", line),
+        },
+    }
+}
+
+/// Prints a line like `println!`, to the given [`Output`] or to the writer registered with
+/// [`set_writer`]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __trace_println {
+    ($output:ident, $($arg:tt)*) => {
+        $crate::__private::write_line(
+            $crate::__private::Output::$output,
+            ::std::format_args!($($arg)*),
+        )
+    };
+}
@@ -0,0 +1,231 @@
+//! Sending the events of traced functions somewhere other than the printed lines
+
+use std::{
+    sync::{Arc, OnceLock, PoisonError, RwLock},
+    time::Duration,
+};
+
+use crate::{__private, filter};
+
+/// The sink registered with [`set_sink`]
+static SINK: RwLock<Option<Arc<dyn TraceSink>>> = RwLock::new(None);
+
+/// Receives the events of every traced call once it's installed with [`set_sink`]
+///
+/// All methods do nothing by default. Functions traced with `tracing` don't report their events to
+/// the sink.
+pub trait TraceSink: Send + Sync {
+    /// Called when a traced function is entered
+    fn on_enter(&self, _event: &EnterEvent<'_>) {}
+
+    /// Called when a traced function returns
+    fn on_exit(&self, _event: &ExitEvent<'_>) {}
+
+    /// Called when a traced function panics, while unwinding
+    fn on_panic(&self, _event: &PanicEvent<'_>) {}
+}
+
+/// A traced function was entered
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct EnterEvent<'a> {
+    /// The name of the function, like `Logger::new` for a method of a traced `impl`
+    pub name: &'static str,
+    /// The path of the module containing the function, as given by `module_path!()`
+    pub module_path: &'static str,
+    /// The file containing the function, as given by `file!()`
+    pub file: &'static str,
+    /// The line the name of the function is on
+    pub line: u32,
+    /// The number of traced calls the function was called from
+    pub depth: usize,
+    /// The names of the printed arguments with their `Debug` representation
+    pub args: &'a [(&'static str, String)],
+}
+
+/// A traced function returned
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ExitEvent<'a> {
+    /// The name of the function, like `Logger::new` for a method of a traced `impl`
+    pub name: &'static str,
+    /// The path of the module containing the function, as given by `module_path!()`
+    pub module_path: &'static str,
+    /// The file containing the function, as given by `file!()`
+    pub file: &'static str,
+    /// The line the name of the function is on
+    pub line: u32,
+    /// The number of traced calls the function was called from
+    pub depth: usize,
+    /// The `Debug` representation of the returned value, unless it's left out by `format_exit`
+    pub return_value: Option<&'a str>,
+    /// How long the call took, if it's measured with `timing`
+    pub elapsed: Option<Duration>,
+}
+
+/// A traced function panicked
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct PanicEvent<'a> {
+    /// The name of the function, like `Logger::new` for a method of a traced `impl`
+    pub name: &'static str,
+    /// The path of the module containing the function, as given by `module_path!()`
+    pub module_path: &'static str,
+    /// The file containing the function, as given by `file!()`
+    pub file: &'static str,
+    /// The line the name of the function is on
+    pub line: u32,
+    /// The number of traced calls the function was called from
+    pub depth: usize,
+    /// The message of the panic, if its payload is a `&str` or a `String`
+    pub message: Option<&'a str>,
+}
+
+/// Sends the events of every traced call in the process to `sink`, instead of printing or logging
+/// them
+///
+/// This replaces the sink registered before, if any. Calls that were entered before the sink is
+/// changed still report their exit to the sink they were entered with.
+pub fn set_sink<S: TraceSink + 'static>(sink: S) {
+    *SINK.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(sink));
+}
+
+/// Removes the sink registered with [`set_sink`], returning it
+///
+/// The events of traced calls are printed or logged again afterwards.
+pub fn reset_sink() -> Option<Arc<dyn TraceSink>> {
+    SINK.write().unwrap_or_else(PoisonError::into_inner).take()
+}
+
+/// Sends the events of every traced call to `sink` until the returned guard is dropped, see
+/// [`set_sink`]
+///
+/// The sink that was registered before is restored when the guard is dropped, and `sink` is dropped
+/// along with it once the calls entered with it have returned. Sinks that write a file, like
+/// [`ChromeSink`](crate::ChromeSink), only complete it when they're dropped.
+///
+/// ```no_run
+/// fn main() -> std::io::Result<()> {
+///     let _guard = trace::sink_scope(trace::ChromeSink::create("trace.json")?);
+///     // Traced calls are written to `trace.json`, which is closed when `main` returns
+///     Ok(())
+/// }
+/// ```
+#[must_use = "the sink is removed again as soon as the guard is dropped"]
+pub fn sink_scope<S: TraceSink + 'static>(sink: S) -> SinkGuard {
+    let sink: Arc<dyn TraceSink> = Arc::new(sink);
+    SinkGuard {
+        previous: SINK
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .replace(sink),
+    }
+}
+
+/// Restores the sink registered before [`sink_scope`] when it's dropped
+pub struct SinkGuard {
+    previous: Option<Arc<dyn TraceSink>>,
+}
+
+impl Drop for SinkGuard {
+    fn drop(&mut self) {
+        let sink = std::mem::replace(
+            &mut *SINK.write().unwrap_or_else(PoisonError::into_inner),
+            self.previous.take(),
+        );
+        // Dropped after the lock is released, as dropping a sink may take a while
+        drop(sink);
+    }
+}
+
+/// Where a traced function is defined
+#[doc(hidden)]
+pub struct Callsite {
+    pub name: &'static str,
+    pub module_path: &'static str,
+    pub file: &'static str,
+    pub line: u32,
+    /// Whether the function passes the `RUST_TRACE` filter, once it's been called
+    traced: OnceLock<bool>,
+}
+
+impl Callsite {
+    pub const fn new(
+        name: &'static str,
+        module_path: &'static str,
+        file: &'static str,
+        line: u32,
+    ) -> Self {
+        Self {
+            name,
+            module_path,
+            file,
+            line,
+            traced: OnceLock::new(),
+        }
+    }
+
+    /// Returns whether the calls of the function are traced according to `RUST_TRACE`
+    ///
+    /// This is checked on every call while tracing is on, so the first one also starts the clock of
+    /// `timestamp = "since_first_call"`, whether the function is traced or not.
+    pub fn is_traced(&self) -> bool {
+        *self.traced.get_or_init(|| {
+            __private::start_clock();
+            filter::is_traced(self.module_path, self.name)
+        })
+    }
+}
+
+#[doc(hidden)]
+pub fn sink() -> Option<Arc<dyn TraceSink>> {
+    SINK.read().unwrap_or_else(PoisonError::into_inner).clone()
+}
+
+#[doc(hidden)]
+pub fn on_enter(
+    sink: &dyn TraceSink,
+    callsite: &Callsite,
+    depth: usize,
+    args: &[(&'static str, String)],
+) {
+    sink.on_enter(&EnterEvent {
+        name: callsite.name,
+        module_path: callsite.module_path,
+        file: callsite.file,
+        line: callsite.line,
+        depth,
+        args,
+    });
+}
+
+#[doc(hidden)]
+pub fn on_exit(
+    sink: &dyn TraceSink,
+    callsite: &Callsite,
+    depth: usize,
+    return_value: Option<&str>,
+    elapsed: Option<Duration>,
+) {
+    sink.on_exit(&ExitEvent {
+        name: callsite.name,
+        module_path: callsite.module_path,
+        file: callsite.file,
+        line: callsite.line,
+        depth,
+        return_value,
+        elapsed,
+    });
+}
+
+#[doc(hidden)]
+pub fn on_panic(sink: &dyn TraceSink, callsite: &Callsite, depth: usize, message: Option<&str>) {
+    sink.on_panic(&PanicEvent {
+        name: callsite.name,
+        module_path: callsite.module_path,
+        file: callsite.file,
+        line: callsite.line,
+        depth,
+        message,
+    });
+}
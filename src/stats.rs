@@ -0,0 +1,215 @@
+//! Per-function statistics of the calls traced with `stats`
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    fmt,
+    io::{self, Write},
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
+
+use crate::{
+    __private::Elapsed,
+    output::{self, Output},
+    sink::Callsite,
+};
+
+/// The statistics of every function traced with `stats`
+static STATS: Mutex<BTreeMap<FnKey, FnStats>> = Mutex::new(BTreeMap::new());
+
+std::thread_local! {
+    /// The number of calls of each function that are being executed on the current thread
+    ///
+    /// An `async fn` that's moved to another thread while it's suspended is counted on the thread
+    /// it was entered on until it returns, so the maximum depth of the functions it calls on the
+    /// other thread is too low, and its own may be too high.
+    static ACTIVE_CALLS: RefCell<HashMap<FnKey, usize>> = RefCell::new(HashMap::new());
+}
+
+/// The qualified name of a traced function, which the statistics are kept by
+///
+/// The methods that `#[trace]` is applied to directly and the ones of a traced `trait` are only
+/// named after the function at compile time, so the `Self` type and trait are part of the key as
+/// well to tell apart the methods with the same name.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct FnKey {
+    module_path: &'static str,
+    self_ty: Option<&'static str>,
+    trait_: Option<&'static str>,
+    name: &'static str,
+}
+
+impl FnKey {
+    fn new(
+        callsite: &Callsite,
+        self_ty: Option<&'static str>,
+        trait_: Option<&'static str>,
+    ) -> Self {
+        Self {
+            module_path: callsite.module_path,
+            self_ty,
+            trait_,
+            name: callsite.name,
+        }
+    }
+}
+
+impl fmt::Display for FnKey {
+    /// Writes the path of the function like the `location` of `#[trace]`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.self_ty, self.trait_) {
+            (Some(self_ty), Some(trait_)) => {
+                write!(f, "<{} as {}>::{}", self_ty, trait_, self.name)
+            }
+            (Some(self_ty), None) => write!(f, "{}::{}", self_ty, self.name),
+            (None, _) => write!(f, "{}::{}", self.module_path, self.name),
+        }
+    }
+}
+
+struct FnStats {
+    calls: u64,
+    total: Duration,
+    min: Duration,
+    max: Duration,
+    max_depth: usize,
+}
+
+/// Prints the statistics of the functions traced with `stats` like the lines of `#[trace]`, so to
+/// stdout or to the writer registered with [`set_writer`](crate::set_writer)
+///
+/// For every function, the report shows the number of calls, their total, minimum, maximum and mean
+/// duration, and the maximum recursion depth it reached, i.e. how many calls of it were being
+/// executed at once on one thread. Calls that panicked are included, but calls that are still being
+/// executed aren't. The statistics keep being collected afterwards.
+///
+/// The maximum depth isn't accurate for `async fn`s that are moved to another thread while they're
+/// suspended, as each thread counts the calls that were entered on it.
+pub fn report() {
+    for line in report_lines() {
+        output::write_line(Output::Stdout, format_args!("{}", line));
+    }
+}
+
+/// Writes the report of [`report`] to `writer`, e.g. a file
+pub fn write_report<W: Write>(mut writer: W) -> io::Result<()> {
+    for line in report_lines() {
+        writeln!(writer, "{}", line)?;
+    }
+    writer.flush()
+}
+
+/// Returns a guard that prints the report of [`report`] when it's dropped
+///
+/// Keep it in a variable at the start of `main` to get the report when the program exits, unless it
+/// exits with [`std::process::exit`].
+#[must_use = "the report is printed as soon as the guard is dropped"]
+pub fn report_at_exit() -> ReportGuard {
+    ReportGuard { _private: () }
+}
+
+/// Prints the report of [`report`] when it's dropped, see [`report_at_exit`]
+pub struct ReportGuard {
+    _private: (),
+}
+
+impl Drop for ReportGuard {
+    fn drop(&mut self) {
+        report();
+    }
+}
+
+fn report_lines() -> Vec<String> {
+    let stats = STATS.lock().unwrap_or_else(PoisonError::into_inner);
+    let rows = stats
+        .iter()
+        .map(|(key, stats)| {
+            [
+                key.to_string(),
+                stats.calls.to_string(),
+                Elapsed(stats.total).to_string(),
+                Elapsed(stats.min).to_string(),
+                Elapsed(stats.max).to_string(),
+                Elapsed(stats.total / stats.calls.try_into().unwrap_or(u32::MAX)).to_string(),
+                stats.max_depth.to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let header = [
+        "function",
+        "calls",
+        "total",
+        "min",
+        "max",
+        "mean",
+        "max depth",
+    ]
+    .map(String::from);
+    let mut widths = header.clone().map(|column| column.chars().count());
+    for row in &rows {
+        for (width, column) in widths.iter_mut().zip(row) {
+            *width = (*width).max(column.chars().count());
+        }
+    }
+
+    std::iter::once(header)
+        .chain(rows)
+        .map(|row| {
+            // The function names are aligned to the left and the numbers to the right
+            let mut line = format!("{:<width$}", row[0], width = widths[0]);
+            for (column, width) in row[1..].iter().zip(&widths[1..]) {
+                line.push_str(&format!("  {:>width$}", column, width = width));
+            }
+            line
+        })
+        .collect()
+}
+
+/// `self_ty` and `trait_` are given for the functions whose `Self` type is only known at runtime
+#[doc(hidden)]
+pub fn stats_enter(
+    callsite: &Callsite,
+    self_ty: Option<&'static str>,
+    trait_: Option<&'static str>,
+) {
+    ACTIVE_CALLS.with(|active_calls| {
+        *active_calls
+            .borrow_mut()
+            .entry(FnKey::new(callsite, self_ty, trait_))
+            .or_default() += 1;
+    });
+}
+
+#[doc(hidden)]
+pub fn stats_exit(
+    callsite: &Callsite,
+    self_ty: Option<&'static str>,
+    trait_: Option<&'static str>,
+    elapsed: Duration,
+) {
+    let key = FnKey::new(callsite, self_ty, trait_);
+    // The call is still counted as being executed here, so this includes it
+    let depth = ACTIVE_CALLS.with(|active_calls| {
+        let mut active_calls = active_calls.borrow_mut();
+        let active = active_calls.entry(key).or_default();
+        let depth = *active;
+        *active = active.saturating_sub(1);
+        depth
+    });
+
+    let mut stats = STATS.lock().unwrap_or_else(PoisonError::into_inner);
+    let stats = stats.entry(key).or_insert(FnStats {
+        calls: 0,
+        total: Duration::ZERO,
+        min: Duration::MAX,
+        max: Duration::ZERO,
+        max_depth: 0,
+    });
+    stats.calls += 1;
+    stats.total += elapsed;
+    stats.min = stats.min.min(elapsed);
+    stats.max = stats.max.max(elapsed);
+    stats.max_depth = stats.max_depth.max(depth);
+}
@@ -0,0 +1,918 @@
+use std::collections::HashSet;
+
+use syn::{self, spanned::Spanned};
+
+pub(crate) struct Args {
+    pub(crate) prefix_enter: String,
+    pub(crate) prefix_exit: String,
+    pub(crate) prefix_panic: String,
+    pub(crate) format_enter: Option<String>,
+    pub(crate) format_exit: Option<String>,
+    pub(crate) filter: Filter,
+    pub(crate) pause: bool,
+    pub(crate) pretty: bool,
+    pub(crate) logging: bool,
+    pub(crate) tracing: bool,
+    pub(crate) level_enter: Level,
+    pub(crate) level_exit: Level,
+    pub(crate) target: Option<String>,
+    pub(crate) output: Output,
+    pub(crate) poll_events: bool,
+    pub(crate) show_self: bool,
+    pub(crate) timing: bool,
+    pub(crate) stats: bool,
+    pub(crate) thread: bool,
+    pub(crate) timestamp: Option<Timestamp>,
+    pub(crate) location: bool,
+    pub(crate) type_names: bool,
+    pub(crate) debug_only: bool,
+}
+
+/// The level of the events emitted by `logging` and `tracing`
+#[derive(Clone, Copy)]
+pub(crate) enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+/// Where the lines are printed when neither `logging` nor `tracing` is used
+#[derive(Clone, Copy)]
+pub(crate) enum Output {
+    Stdout,
+    Stderr,
+}
+
+/// The clock of the timestamps printed with `timestamp` or interpolated with `{time}`
+#[derive(Clone, Copy)]
+pub(crate) enum Timestamp {
+    /// The time of day in UTC
+    Wall,
+    /// The time since the first call of a traced function
+    SinceFirstCall,
+}
+
+pub(crate) enum Filter {
+    None,
+    Enable(HashSet<proc_macro2::Ident>),
+    Disable(HashSet<proc_macro2::Ident>),
+}
+
+const DEFAULT_PREFIX_ENTER: &str = "[+]";
+const DEFAULT_PREFIX_EXIT: &str = "[-]";
+const DEFAULT_PREFIX_PANIC: &str = "[!]";
+const DEFAULT_PAUSE: bool = false;
+const DEFAULT_PRETTY: bool = false;
+const DEFAULT_LOGGING: bool = false;
+const DEFAULT_TRACING: bool = false;
+const DEFAULT_LEVEL: Level = Level::Trace;
+const DEFAULT_OUTPUT: Output = Output::Stdout;
+const DEFAULT_POLL_EVENTS: bool = false;
+const DEFAULT_SHOW_SELF: bool = false;
+const DEFAULT_TIMING: bool = false;
+const DEFAULT_STATS: bool = false;
+const DEFAULT_THREAD: bool = false;
+const DEFAULT_LOCATION: bool = false;
+const DEFAULT_TYPE_NAMES: bool = false;
+// Every `#[trace]` is `debug_only` with the `debug-only` feature of `trace`
+const DEFAULT_DEBUG_ONLY: bool = cfg!(feature = "debug-only");
+
+macro_rules! try_extract_str {
+    ($lit:expr, $meta:expr, $arg_ty:ident) => {{
+        match *$lit {
+            syn::Lit::Str(ref lit_str) => Ok(Arg::$arg_ty($meta.span(), lit_str.value())),
+            _ => Err(vec![syn::Error::new_spanned(
+                $lit,
+                format!("`{}` must have a string value", stringify!($arg_ty)),
+            )]),
+        }
+    }};
+}
+
+impl Level {
+    fn from_lit(lit: &syn::Lit, arg_name: &str) -> Result<Self, Vec<syn::Error>> {
+        let level_error = || {
+            vec![syn::Error::new_spanned(
+                lit,
+                format!(
+                    "`{}` must be one of \"error\", \"warn\", \"info\", \"debug\" or \"trace\"",
+                    arg_name
+                ),
+            )]
+        };
+
+        match *lit {
+            syn::Lit::Str(ref lit_str) => match lit_str.value().to_lowercase().as_str() {
+                "error" => Ok(Level::Error),
+                "warn" => Ok(Level::Warn),
+                "info" => Ok(Level::Info),
+                "debug" => Ok(Level::Debug),
+                "trace" => Ok(Level::Trace),
+                _ => Err(level_error()),
+            },
+            _ => Err(level_error()),
+        }
+    }
+}
+
+impl Output {
+    fn from_lit(lit: &syn::Lit) -> Result<Self, Vec<syn::Error>> {
+        let output_error = || {
+            vec![syn::Error::new_spanned(
+                lit,
+                "`output` must be either \"stdout\" or \"stderr\"",
+            )]
+        };
+
+        match *lit {
+            syn::Lit::Str(ref lit_str) => match lit_str.value().as_str() {
+                "stdout" => Ok(Output::Stdout),
+                "stderr" => Ok(Output::Stderr),
+                _ => Err(output_error()),
+            },
+            _ => Err(output_error()),
+        }
+    }
+}
+
+impl Timestamp {
+    fn from_lit(lit: &syn::Lit) -> Result<Self, Vec<syn::Error>> {
+        let timestamp_error = || {
+            vec![syn::Error::new_spanned(
+                lit,
+                "`timestamp` must be either \"wall\" or \"since_first_call\"",
+            )]
+        };
+
+        match *lit {
+            syn::Lit::Str(ref lit_str) => match lit_str.value().as_str() {
+                "wall" => Ok(Timestamp::Wall),
+                "since_first_call" => Ok(Timestamp::SinceFirstCall),
+                _ => Err(timestamp_error()),
+            },
+            _ => Err(timestamp_error()),
+        }
+    }
+}
+
+impl Args {
+    pub(crate) fn from_raw_args(raw_args: syn::AttributeArgs) -> Result<Self, Vec<syn::Error>> {
+        // Different types of arguments accepted by `#[trace]`;
+        // spans are needed for friendly error reporting of duplicate arguments
+        enum Arg {
+            PrefixEnter(proc_macro2::Span, String),
+            PrefixExit(proc_macro2::Span, String),
+            PrefixPanic(proc_macro2::Span, String),
+            Enable(proc_macro2::Span, HashSet<proc_macro2::Ident>),
+            Disable(proc_macro2::Span, HashSet<proc_macro2::Ident>),
+            Pause(proc_macro2::Span, bool),
+            Pretty(proc_macro2::Span, bool),
+            Logging(proc_macro2::Span, Option<Level>),
+            Tracing(proc_macro2::Span, Option<Level>),
+            LevelEnter(proc_macro2::Span, Level),
+            LevelExit(proc_macro2::Span, Level),
+            Target(proc_macro2::Span, String),
+            Output(proc_macro2::Span, Output),
+            PollEvents(proc_macro2::Span, bool),
+            ShowSelf(proc_macro2::Span, bool),
+            Timing(proc_macro2::Span, bool),
+            Stats(proc_macro2::Span, bool),
+            Thread(proc_macro2::Span, bool),
+            Timestamp(proc_macro2::Span, Timestamp),
+            Location(proc_macro2::Span, bool),
+            TypeNames(proc_macro2::Span, bool),
+            DebugOnly(proc_macro2::Span, bool),
+            FormatEnter(proc_macro2::Span, String),
+            FormatExit(proc_macro2::Span, String),
+        }
+
+        // Parse arguments
+        let args_res = raw_args.into_iter().map(|nested_meta| match nested_meta {
+            syn::NestedMeta::Meta(ref meta) => {
+                enum ArgName {
+                    PrefixEnter,
+                    PrefixExit,
+                    PrefixPanic,
+                    FormatEnter,
+                    FormatExit,
+                    Enable,
+                    Disable,
+                    Pause,
+                    Pretty,
+                    Logging,
+                    Tracing,
+                    LevelEnter,
+                    LevelExit,
+                    Target,
+                    Output,
+                    PollEvents,
+                    ShowSelf,
+                    Timing,
+                    Stats,
+                    Thread,
+                    Timestamp,
+                    Location,
+                    TypeNames,
+                    DebugOnly,
+                }
+
+                let ident = &meta.path().segments.first().unwrap().ident;
+                let arg_name = match ident.to_string().as_str() {
+                    "prefix_enter" => ArgName::PrefixEnter,
+                    "prefix_exit" => ArgName::PrefixExit,
+                    "prefix_panic" => ArgName::PrefixPanic,
+                    "format_enter" => ArgName::FormatEnter,
+                    "format_exit" => ArgName::FormatExit,
+                    "enable" => ArgName::Enable,
+                    "disable" => ArgName::Disable,
+                    "pause" => ArgName::Pause,
+                    "pretty" => ArgName::Pretty,
+                    "logging" => ArgName::Logging,
+                    "tracing" => ArgName::Tracing,
+                    "level_enter" => ArgName::LevelEnter,
+                    "level_exit" => ArgName::LevelExit,
+                    "target" => ArgName::Target,
+                    "output" => ArgName::Output,
+                    "poll_events" => ArgName::PollEvents,
+                    "show_self" => ArgName::ShowSelf,
+                    "timing" => ArgName::Timing,
+                    "stats" => ArgName::Stats,
+                    "thread" => ArgName::Thread,
+                    "timestamp" => ArgName::Timestamp,
+                    "location" => ArgName::Location,
+                    "type_names" => ArgName::TypeNames,
+                    "debug_only" => ArgName::DebugOnly,
+                    _ => {
+                        return Err(vec![syn::Error::new_spanned(
+                            ident.clone(),
+                            format_args!("unknown attribute argument `{}`", ident),
+                        )])
+                    }
+                };
+
+                let prefix_enter_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`prefix_enter` requires a string value",
+                    )]
+                };
+                let prefix_exit_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`prefix_exit` requires a string value",
+                    )]
+                };
+                let prefix_panic_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`prefix_panic` requires a string value",
+                    )]
+                };
+                let format_enter_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`format_enter` requires a string value",
+                    )]
+                };
+                let format_exit_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`format_exit` requires a string value",
+                    )]
+                };
+                let enable_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`enable` requires a list of meta words",
+                    )]
+                };
+                let disable_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`disable` requires a list of meta words",
+                    )]
+                };
+                let pause_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`pause` must be a meta word",
+                    )]
+                };
+                let pretty_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`pretty` must be a meta word",
+                    )]
+                };
+                let logging_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`logging` must be a meta word or have a string value",
+                    )]
+                };
+                let tracing_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`tracing` must be a meta word or have a string value",
+                    )]
+                };
+                let level_enter_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`level_enter` requires a string value",
+                    )]
+                };
+                let level_exit_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`level_exit` requires a string value",
+                    )]
+                };
+                let target_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`target` requires a string value",
+                    )]
+                };
+                let output_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`output` requires a string value",
+                    )]
+                };
+                let poll_events_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`poll_events` must be a meta word",
+                    )]
+                };
+                let show_self_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`show_self` must be a meta word",
+                    )]
+                };
+                let timing_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`timing` must be a meta word",
+                    )]
+                };
+                let stats_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`stats` must be a meta word",
+                    )]
+                };
+                let thread_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`thread` must be a meta word",
+                    )]
+                };
+                let location_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`location` must be a meta word",
+                    )]
+                };
+                let type_names_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`type_names` must be a meta word",
+                    )]
+                };
+                let debug_only_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`debug_only` must be a meta word",
+                    )]
+                };
+                let timestamp_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`timestamp` must be a meta word or have a string value",
+                    )]
+                };
+
+                match *meta {
+                    syn::Meta::Path(_) => match arg_name {
+                        ArgName::Pause => Ok(Arg::Pause(meta.span(), true)),
+                        ArgName::Pretty => Ok(Arg::Pretty(meta.span(), true)),
+                        ArgName::Logging => Ok(Arg::Logging(meta.span(), None)),
+                        ArgName::Tracing => Ok(Arg::Tracing(meta.span(), None)),
+                        ArgName::LevelEnter => Err(level_enter_type_error()),
+                        ArgName::LevelExit => Err(level_exit_type_error()),
+                        ArgName::Target => Err(target_type_error()),
+                        ArgName::Output => Err(output_type_error()),
+                        ArgName::PollEvents => Ok(Arg::PollEvents(meta.span(), true)),
+                        ArgName::ShowSelf => Ok(Arg::ShowSelf(meta.span(), true)),
+                        ArgName::Timing => Ok(Arg::Timing(meta.span(), true)),
+                        ArgName::Stats => Ok(Arg::Stats(meta.span(), true)),
+                        ArgName::Thread => Ok(Arg::Thread(meta.span(), true)),
+                        ArgName::Location => Ok(Arg::Location(meta.span(), true)),
+                        ArgName::TypeNames => Ok(Arg::TypeNames(meta.span(), true)),
+                        ArgName::DebugOnly => Ok(Arg::DebugOnly(meta.span(), true)),
+                        ArgName::Timestamp => Ok(Arg::Timestamp(meta.span(), Timestamp::Wall)),
+                        ArgName::PrefixEnter => Err(prefix_enter_type_error()),
+                        ArgName::PrefixExit => Err(prefix_exit_type_error()),
+                        ArgName::PrefixPanic => Err(prefix_panic_type_error()),
+                        ArgName::Enable => Err(enable_type_error()),
+                        ArgName::Disable => Err(disable_type_error()),
+                        ArgName::FormatEnter => Err(format_enter_type_error()),
+                        ArgName::FormatExit => Err(format_exit_type_error()),
+                    },
+                    syn::Meta::List(syn::MetaList { ref nested, .. }) => match arg_name {
+                        ArgName::Enable => {
+                            let mut idents = HashSet::new();
+                            let mut other_nested_meta_errors = Vec::new();
+
+                            nested.iter().for_each(|nested_meta| match *nested_meta {
+                                syn::NestedMeta::Meta(syn::Meta::Path(ref path))
+                                    if path.segments.len() == 1 =>
+                                {
+                                    idents.insert(path.segments.first().unwrap().ident.clone());
+                                }
+                                _ => other_nested_meta_errors.push(syn::Error::new_spanned(
+                                    nested_meta,
+                                    "`enable` must contain single ident paths only",
+                                )),
+                            });
+
+                            if other_nested_meta_errors.is_empty() {
+                                Ok(Arg::Enable(meta.span(), idents))
+                            } else {
+                                Err(other_nested_meta_errors)
+                            }
+                        }
+                        ArgName::Disable => {
+                            let mut idents = HashSet::new();
+                            let mut other_nested_meta_errors = Vec::new();
+
+                            nested.iter().for_each(|nested_meta| match *nested_meta {
+                                syn::NestedMeta::Meta(syn::Meta::Path(ref path))
+                                    if path.segments.len() == 1 =>
+                                {
+                                    idents.insert(path.segments.first().unwrap().ident.clone());
+                                }
+                                _ => other_nested_meta_errors.push(syn::Error::new_spanned(
+                                    nested_meta,
+                                    "`disable` must contain single ident paths only",
+                                )),
+                            });
+
+                            if other_nested_meta_errors.is_empty() {
+                                Ok(Arg::Disable(meta.span(), idents))
+                            } else {
+                                Err(other_nested_meta_errors)
+                            }
+                        }
+
+                        ArgName::PrefixEnter => Err(prefix_enter_type_error()),
+                        ArgName::PrefixExit => Err(prefix_exit_type_error()),
+                        ArgName::PrefixPanic => Err(prefix_panic_type_error()),
+                        ArgName::Pause => Err(pause_type_error()),
+                        ArgName::Pretty => Err(pretty_type_error()),
+                        ArgName::Logging => Err(logging_type_error()),
+                        ArgName::Tracing => Err(tracing_type_error()),
+                        ArgName::LevelEnter => Err(level_enter_type_error()),
+                        ArgName::LevelExit => Err(level_exit_type_error()),
+                        ArgName::Target => Err(target_type_error()),
+                        ArgName::Output => Err(output_type_error()),
+                        ArgName::PollEvents => Err(poll_events_type_error()),
+                        ArgName::ShowSelf => Err(show_self_type_error()),
+                        ArgName::Timing => Err(timing_type_error()),
+                        ArgName::Stats => Err(stats_type_error()),
+                        ArgName::Thread => Err(thread_type_error()),
+                        ArgName::Location => Err(location_type_error()),
+                        ArgName::TypeNames => Err(type_names_type_error()),
+                        ArgName::DebugOnly => Err(debug_only_type_error()),
+                        ArgName::Timestamp => Err(timestamp_type_error()),
+                        ArgName::FormatEnter => Err(format_enter_type_error()),
+                        ArgName::FormatExit => Err(format_exit_type_error()),
+                    },
+                    syn::Meta::NameValue(syn::MetaNameValue { ref lit, .. }) => match arg_name {
+                        ArgName::PrefixEnter => try_extract_str!(lit, meta, PrefixEnter),
+                        ArgName::PrefixExit => try_extract_str!(lit, meta, PrefixExit),
+                        ArgName::PrefixPanic => try_extract_str!(lit, meta, PrefixPanic),
+                        ArgName::FormatEnter => try_extract_str!(lit, meta, FormatEnter),
+                        ArgName::FormatExit => try_extract_str!(lit, meta, FormatExit),
+                        ArgName::Enable => Err(enable_type_error()),
+                        ArgName::Disable => Err(disable_type_error()),
+                        ArgName::Pause => Err(pause_type_error()),
+                        ArgName::Pretty => Err(pretty_type_error()),
+                        ArgName::Logging => Level::from_lit(lit, "logging")
+                            .map(|level| Arg::Logging(meta.span(), Some(level))),
+                        ArgName::Tracing => Level::from_lit(lit, "tracing")
+                            .map(|level| Arg::Tracing(meta.span(), Some(level))),
+                        ArgName::LevelEnter => Level::from_lit(lit, "level_enter")
+                            .map(|level| Arg::LevelEnter(meta.span(), level)),
+                        ArgName::LevelExit => Level::from_lit(lit, "level_exit")
+                            .map(|level| Arg::LevelExit(meta.span(), level)),
+                        ArgName::Target => try_extract_str!(lit, meta, Target),
+                        ArgName::Output => {
+                            Output::from_lit(lit).map(|output| Arg::Output(meta.span(), output))
+                        }
+                        ArgName::PollEvents => Err(poll_events_type_error()),
+                        ArgName::ShowSelf => Err(show_self_type_error()),
+                        ArgName::Timing => Err(timing_type_error()),
+                        ArgName::Stats => Err(stats_type_error()),
+                        ArgName::Thread => Err(thread_type_error()),
+                        ArgName::Location => Err(location_type_error()),
+                        ArgName::TypeNames => Err(type_names_type_error()),
+                        ArgName::DebugOnly => Err(debug_only_type_error()),
+                        ArgName::Timestamp => Timestamp::from_lit(lit)
+                            .map(|timestamp| Arg::Timestamp(meta.span(), timestamp)),
+                    },
+                }
+            }
+            syn::NestedMeta::Lit(_) => Err(vec![syn::Error::new_spanned(
+                nested_meta,
+                "literal attribute not allowed",
+            )]),
+        });
+
+        let mut prefix_enter_args = vec![];
+        let mut prefix_exit_args = vec![];
+        let mut prefix_panic_args = vec![];
+        let mut format_enter_args = vec![];
+        let mut format_exit_args = vec![];
+        let mut enable_args = vec![];
+        let mut disable_args = vec![];
+        let mut pause_args = vec![];
+        let mut pretty_args = vec![];
+        let mut logging_args = vec![];
+        let mut tracing_args = vec![];
+        let mut level_enter_args = vec![];
+        let mut level_exit_args = vec![];
+        let mut target_args = vec![];
+        let mut output_args = vec![];
+        let mut poll_events_args = vec![];
+        let mut show_self_args = vec![];
+        let mut timing_args = vec![];
+        let mut stats_args = vec![];
+        let mut thread_args = vec![];
+        let mut timestamp_args = vec![];
+        let mut location_args = vec![];
+        let mut type_names_args = vec![];
+        let mut debug_only_args = vec![];
+        let mut errors = vec![];
+
+        // Group arguments of the same type and errors
+        for arg_res in args_res {
+            match arg_res {
+                Ok(arg) => match arg {
+                    Arg::PrefixEnter(span, s) => prefix_enter_args.push((span, s)),
+                    Arg::PrefixExit(span, s) => prefix_exit_args.push((span, s)),
+                    Arg::PrefixPanic(span, s) => prefix_panic_args.push((span, s)),
+                    Arg::Enable(span, idents) => enable_args.push((span, idents)),
+                    Arg::Disable(span, idents) => disable_args.push((span, idents)),
+                    Arg::Pause(span, b) => pause_args.push((span, b)),
+                    Arg::Pretty(span, b) => pretty_args.push((span, b)),
+                    Arg::Logging(span, level) => logging_args.push((span, level)),
+                    Arg::Tracing(span, level) => tracing_args.push((span, level)),
+                    Arg::LevelEnter(span, level) => level_enter_args.push((span, level)),
+                    Arg::LevelExit(span, level) => level_exit_args.push((span, level)),
+                    Arg::Target(span, s) => target_args.push((span, s)),
+                    Arg::Output(span, output) => output_args.push((span, output)),
+                    Arg::PollEvents(span, b) => poll_events_args.push((span, b)),
+                    Arg::ShowSelf(span, b) => show_self_args.push((span, b)),
+                    Arg::Timing(span, b) => timing_args.push((span, b)),
+                    Arg::Stats(span, b) => stats_args.push((span, b)),
+                    Arg::Thread(span, b) => thread_args.push((span, b)),
+                    Arg::Timestamp(span, timestamp) => timestamp_args.push((span, timestamp)),
+                    Arg::Location(span, b) => location_args.push((span, b)),
+                    Arg::TypeNames(span, b) => type_names_args.push((span, b)),
+                    Arg::DebugOnly(span, b) => debug_only_args.push((span, b)),
+                    Arg::FormatEnter(span, s) => format_enter_args.push((span, s)),
+                    Arg::FormatExit(span, s) => format_exit_args.push((span, s)),
+                },
+                Err(es) => errors.extend(es),
+            }
+        }
+
+        // Report duplicates
+        if prefix_enter_args.len() >= 2 {
+            errors.extend(
+                prefix_enter_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `prefix_enter`")),
+            );
+        }
+        if prefix_exit_args.len() >= 2 {
+            errors.extend(
+                prefix_exit_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `prefix_exit`")),
+            );
+        }
+        if prefix_panic_args.len() >= 2 {
+            errors.extend(
+                prefix_panic_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `prefix_panic`")),
+            );
+        }
+        if format_enter_args.len() >= 2 {
+            errors.extend(
+                format_enter_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `format_enter`")),
+            );
+        }
+        if format_exit_args.len() >= 2 {
+            errors.extend(
+                format_exit_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `format_exit`")),
+            );
+        }
+        if enable_args.len() >= 2 {
+            errors.extend(
+                enable_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `enable`")),
+            );
+        }
+        if disable_args.len() >= 2 {
+            errors.extend(
+                disable_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `disable`")),
+            );
+        }
+        if pause_args.len() >= 2 {
+            errors.extend(
+                pause_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `pause`")),
+            );
+        }
+        if pretty_args.len() >= 2 {
+            errors.extend(
+                pretty_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `pretty`")),
+            );
+        }
+        if logging_args.len() >= 2 {
+            errors.extend(
+                logging_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `logging`")),
+            );
+        }
+        if tracing_args.len() >= 2 {
+            errors.extend(
+                tracing_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `tracing`")),
+            );
+        }
+        if level_enter_args.len() >= 2 {
+            errors.extend(
+                level_enter_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `level_enter`")),
+            );
+        }
+        if level_exit_args.len() >= 2 {
+            errors.extend(
+                level_exit_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `level_exit`")),
+            );
+        }
+        if target_args.len() >= 2 {
+            errors.extend(
+                target_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `target`")),
+            );
+        }
+        if output_args.len() >= 2 {
+            errors.extend(
+                output_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `output`")),
+            );
+        }
+        if poll_events_args.len() >= 2 {
+            errors.extend(
+                poll_events_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `poll_events`")),
+            );
+        }
+        if show_self_args.len() >= 2 {
+            errors.extend(
+                show_self_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `show_self`")),
+            );
+        }
+        if timing_args.len() >= 2 {
+            errors.extend(
+                timing_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `timing`")),
+            );
+        }
+        if stats_args.len() >= 2 {
+            errors.extend(
+                stats_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `stats`")),
+            );
+        }
+        if thread_args.len() >= 2 {
+            errors.extend(
+                thread_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `thread`")),
+            );
+        }
+        if timestamp_args.len() >= 2 {
+            errors.extend(
+                timestamp_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `timestamp`")),
+            );
+        }
+        if location_args.len() >= 2 {
+            errors.extend(
+                location_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `location`")),
+            );
+        }
+        if type_names_args.len() >= 2 {
+            errors.extend(
+                type_names_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `type_names`")),
+            );
+        }
+        if debug_only_args.len() >= 2 {
+            errors.extend(
+                debug_only_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `debug_only`")),
+            );
+        }
+
+        // Report the presence of mutually exclusive arguments
+        if enable_args.len() == 1 && disable_args.len() == 1 {
+            errors.push(syn::Error::new(
+                enable_args[0].0,
+                "cannot have both `enable` and `disable`",
+            ));
+            errors.push(syn::Error::new(
+                disable_args[0].0,
+                "cannot have both `enable` and `disable`",
+            ));
+        }
+        if pretty_args.len() == 1 && format_enter_args.len() == 1 {
+            errors.push(syn::Error::new(
+                pretty_args[0].0,
+                "cannot have both `pretty` and `format_enter`",
+            ));
+            errors.push(syn::Error::new(
+                format_enter_args[0].0,
+                "cannot have both `pretty` and `format_enter`",
+            ));
+        }
+        if pretty_args.len() == 1 && format_exit_args.len() == 1 {
+            errors.push(syn::Error::new(
+                pretty_args[0].0,
+                "cannot have both `pretty` and `format_exit`",
+            ));
+            errors.push(syn::Error::new(
+                format_exit_args[0].0,
+                "cannot have both `pretty` and `format_exit`",
+            ));
+        }
+        if logging_args.len() == 1 && tracing_args.len() == 1 {
+            errors.push(syn::Error::new(
+                logging_args[0].0,
+                "cannot have both `logging` and `tracing`",
+            ));
+            errors.push(syn::Error::new(
+                tracing_args[0].0,
+                "cannot have both `logging` and `tracing`",
+            ));
+        }
+        if logging_args.len() == 1 && output_args.len() == 1 {
+            errors.push(syn::Error::new(
+                logging_args[0].0,
+                "cannot have both `logging` and `output`",
+            ));
+            errors.push(syn::Error::new(
+                output_args[0].0,
+                "cannot have both `logging` and `output`",
+            ));
+        }
+        if tracing_args.len() == 1 && output_args.len() == 1 {
+            errors.push(syn::Error::new(
+                tracing_args[0].0,
+                "cannot have both `tracing` and `output`",
+            ));
+            errors.push(syn::Error::new(
+                output_args[0].0,
+                "cannot have both `tracing` and `output`",
+            ));
+        }
+
+        // Report arguments that only make sense with another one
+        if logging_args.is_empty() && tracing_args.is_empty() {
+            errors.extend(
+                level_enter_args
+                    .iter()
+                    .map(|(span, _)| (span, "`level_enter`"))
+                    .chain(
+                        level_exit_args
+                            .iter()
+                            .map(|(span, _)| (span, "`level_exit`")),
+                    )
+                    .chain(target_args.iter().map(|(span, _)| (span, "`target`")))
+                    .map(|(span, name)| {
+                        syn::Error::new(*span, format!("{} requires `logging` or `tracing`", name))
+                    }),
+            );
+        }
+
+        if errors.is_empty() {
+            macro_rules! first_no_span {
+                ($iterable:expr) => {
+                    $iterable.into_iter().next().map(|(_, elem)| elem)
+                };
+            }
+
+            let prefix_enter = first_no_span!(prefix_enter_args)
+                .unwrap_or_else(|| DEFAULT_PREFIX_ENTER.to_owned());
+            let prefix_exit =
+                first_no_span!(prefix_exit_args).unwrap_or_else(|| DEFAULT_PREFIX_EXIT.to_owned());
+            let prefix_panic = first_no_span!(prefix_panic_args)
+                .unwrap_or_else(|| DEFAULT_PREFIX_PANIC.to_owned());
+            let format_enter = first_no_span!(format_enter_args);
+            let format_exit = first_no_span!(format_exit_args);
+            let filter = match (first_no_span!(enable_args), first_no_span!(disable_args)) {
+                (None, None) => Filter::None,
+                (Some(idents), None) => Filter::Enable(idents),
+                (None, Some(idents)) => Filter::Disable(idents),
+                (Some(_), Some(_)) => unreachable!(),
+            };
+            let pause = first_no_span!(pause_args).unwrap_or(DEFAULT_PAUSE);
+            let pretty = first_no_span!(pretty_args).unwrap_or(DEFAULT_PRETTY);
+            let logging = !logging_args.is_empty() || DEFAULT_LOGGING;
+            let tracing = !tracing_args.is_empty() || DEFAULT_TRACING;
+            let level = first_no_span!(logging_args)
+                .or_else(|| first_no_span!(tracing_args))
+                .flatten()
+                .unwrap_or(DEFAULT_LEVEL);
+            let level_enter = first_no_span!(level_enter_args).unwrap_or(level);
+            let level_exit = first_no_span!(level_exit_args).unwrap_or(level);
+            let target = first_no_span!(target_args);
+            let output = first_no_span!(output_args).unwrap_or(DEFAULT_OUTPUT);
+            let poll_events = first_no_span!(poll_events_args).unwrap_or(DEFAULT_POLL_EVENTS);
+            let show_self = first_no_span!(show_self_args).unwrap_or(DEFAULT_SHOW_SELF);
+            let timing = first_no_span!(timing_args).unwrap_or(DEFAULT_TIMING);
+            let stats = first_no_span!(stats_args).unwrap_or(DEFAULT_STATS);
+            let thread = first_no_span!(thread_args).unwrap_or(DEFAULT_THREAD);
+            let timestamp = first_no_span!(timestamp_args);
+            let location = first_no_span!(location_args).unwrap_or(DEFAULT_LOCATION);
+            let type_names = first_no_span!(type_names_args).unwrap_or(DEFAULT_TYPE_NAMES);
+            let debug_only = first_no_span!(debug_only_args).unwrap_or(DEFAULT_DEBUG_ONLY);
+
+            Ok(Self {
+                prefix_enter,
+                prefix_exit,
+                prefix_panic,
+                filter,
+                pause,
+                pretty,
+                logging,
+                tracing,
+                level_enter,
+                level_exit,
+                target,
+                output,
+                poll_events,
+                show_self,
+                timing,
+                stats,
+                thread,
+                timestamp,
+                location,
+                type_names,
+                debug_only,
+                format_enter,
+                format_exit,
+            })
+        } else {
+            Err(errors)
+        }
+    }
+}
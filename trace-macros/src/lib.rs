@@ -0,0 +1,1598 @@
+//! Procedural macros for the [`trace`](https://docs.rs/trace) crate.
+//!
+//! This crate shouldn't be used directly. Depend on `trace` instead, which re-exports these macros
+//! together with the runtime support that the generated code refers to.
+
+mod args;
+
+use std::{iter::Peekable, str::Chars};
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned, ToTokens};
+use syn::{
+    parse::{Parse, Parser},
+    parse_quote,
+    visit_mut::VisitMut,
+};
+
+/// Kept for backward compatibility, expands to nothing
+///
+/// `#[trace]` used to require a `DEPTH` variable to be declared with this macro in the scope of
+/// traced functions. The generated code now refers to the runtime support in `trace` directly, so
+/// calling this macro is no longer needed.
+///
+/// ```
+/// use trace::trace;
+///
+/// trace::init_depth_var!();
+///
+/// #[trace]
+/// fn foo() {}
+/// #
+/// # fn main() {
+/// #     foo();
+/// # }
+/// ```
+#[proc_macro]
+pub fn init_depth_var(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let output = if input.is_empty() {
+        quote!()
+    } else {
+        let input2 = proc_macro2::TokenStream::from(input);
+        syn::Error::new_spanned(input2, "`init_depth_var` takes no arguments").to_compile_error()
+    };
+
+    output.into()
+}
+
+/// Enables tracing the execution of functions
+///
+/// The methods of an `impl` that `#[trace]` is applied to are named after their `Self` type, like
+/// `Logger::new`, or after their `Self` type and trait, like `<Logger as Log>::log`.
+///
+/// It supports the following optional arguments (see the `examples` folder for examples of using
+/// each of these):
+///
+/// - `prefix_enter` - The prefix of the `println!` statement when a function is entered. Defaults
+///   to `[+]`.
+///
+/// - `prefix_exit` - The prefix of the `println!` statement when a function is exited. Defaults to
+///   `[-]`.
+///
+/// - `prefix_panic` - The prefix of the `println!` statement when a function panics. The panic
+///   message is printed as well if the payload is a `&str` or `String`. Defaults to `[!]`.
+///
+/// - `enable` - When applied to a `mod`, `impl` or `trait`, `enable` takes a list of function names
+///   to print, not printing any functions that are not part of this list. All functions are enabled
+///   by default. When applied to a method or a function, `enable` takes a list of arguments to
+///   print, not printing any arguments that are not part of the list. All arguments are enabled by
+///   default.
+///
+/// - `disable` - When applied to a `mod`, `impl` or `trait`, `disable` takes a list of function
+///   names to not print, printing all other functions in the `mod`, `impl` or `trait`. No functions
+///   are disabled by default. When applied to a method or a function, `disable` takes a list of
+///   arguments to not print, printing all other arguments. No arguments are disabled by default.
+///
+/// - `show_self` - Print the `self` receiver of methods as well, which requires `Self` to implement
+///   `Debug`. Naming `self` in `enable` also prints it, and naming it in `disable` hides it.
+///   Disabled by default.
+///
+/// - `pause` - When given as an argument to `#[trace]`, execution is paused after each line of
+///   tracing output until enter is pressed. This allows you to trace through a program step by
+///   step. Disabled by default.
+///
+/// - `pretty` - Pretty print the output (use `{:#?}` instead of `{:?}`). Disabled by default.
+///
+/// - `logging` - Use the `log` crate instead of `println`. Lines are logged at the trace level,
+///   unless a level is given like `logging = "debug"`. The levels are `"error"`, `"warn"`,
+///   `"info"`, `"debug"` and `"trace"`. Disabled by default.
+///
+/// - `tracing` - Report each call as a span of the `tracing` crate instead of printing it. The span
+///   is named after the function, the arguments are recorded as its fields and the return value is
+///   recorded as a `return_value` field when the call returns, so the installed subscriber decides
+///   how calls are rendered. The prefix, format, `timing`, `stats`, `thread`, `timestamp` and
+///   `location` arguments don't apply to spans, and neither does a `TraceSink`. Requires the
+///   `tracing` feature of `trace`, and cannot be used together with `logging`. Like with `logging`,
+///   a level can be given, e.g. `tracing = "debug"`, which is the level of the span. Disabled by
+///   default.
+///
+/// - `level_enter` - The level of the lines logged when a function is entered (or resumed), which
+///   takes precedence over the level given to `logging` or `tracing`. With `tracing`, this is the
+///   level of the span.
+///
+/// - `level_exit` - The level of the lines logged when a function is exited (or suspended or
+///   panics), which takes precedence over the level given to `logging` or `tracing`. With
+///   `tracing`, this is the level of the event reporting a panic.
+///
+/// - `output` - Where the lines are printed, either `"stdout"` or `"stderr"`. A writer registered
+///   with `trace::set_writer` takes precedence over this. Cannot be used together with `logging` or
+///   `tracing`. Defaults to `"stdout"`.
+///
+/// - `target` - The target of the logged lines, or of the span with `tracing`. Defaults to the path
+///   of the module containing the traced function.
+///
+/// - `poll_events` - For an `async fn`, print a `[~] Suspended` line every time its future returns
+///   `Poll::Pending` and a `[~] Resumed` line every time it's polled again. The arguments are
+///   repeated on these lines so that interleaved calls can be told apart. Disabled by default.
+///
+/// - `format_enter` - The format (anything after the prefix) of `println!` statements when a function
+///   is entered. Allows parameter interpolation like:
+/// ```rust
+/// # use trace::trace;
+/// #[trace(format_enter = "i is {i}")]
+/// fn foo(i: i32) {
+///     println!("foo")
+/// }
+/// ```
+///   Interpolation follows the same rules as `format!()` besides for the fact that there is no pretty printing,
+///   that is anything interpolated will be debug formatted. The name of the current thread and the
+///   current time can be interpolated with `{thread}` and `{time}`, unless an argument has the same
+///   name. Disabled by default.
+///
+/// - `format_exit` - The format (anything after the prefix) of `println!` statements when a function
+///   is exited. To interpolate the return value use `{r}`:
+/// ```rust
+/// # use trace::trace;
+/// #[trace(format_exit = "returning {r}")]
+/// fn foo() -> i32 {
+///     1
+/// }
+/// ```
+///   With `timing`, the duration of the call can be interpolated with `{elapsed}`. Otherwise
+///   formatting follows the same rules as `format_enter`, including `{thread}` and `{time}`.
+///   Disabled by default.
+///
+/// - `timing` - Measure how long each call takes and print it after the return value, like
+///   `[-] Exiting foo = 3 (1.24ms)`. The duration is also passed to a `TraceSink`. Disabled by
+///   default.
+///
+/// - `thread` - Start each line with the name of the thread it's printed from, or its id if the
+///   thread has no name, like `[worker-1] [+] Entering foo()`. Disabled by default.
+///
+/// - `timestamp` - Start each line with the current time, either the time of day in UTC with
+///   `timestamp` or `timestamp = "wall"`, like `14:03:27.123456`, or the seconds since a traced
+///   function was first called with `timestamp = "since_first_call"`, like `0.001234`. The clock
+///   starts at the first call of any function with `#[trace]` while tracing is on, whether it's
+///   printed or not. `{time}` is interpolated as the same kind of timestamp. Disabled by default.
+///
+/// - `location` - Name the function by its path, like `parser::Parser::new`, so that functions
+///   with the same name can be told apart. The path of a method starts with its `Self` type as
+///   given by `std::any::type_name`, and a method of a trait is named like
+///   `<parser::Parser as Describe>::describe`. When `#[trace]` is applied to a method directly
+///   instead of its `impl` or `trait`, its trait isn't known, and it's only known to be a method if
+///   it takes `self` or mentions `Self` in its signature. The Entering line also ends with the file
+///   and line where the function is defined, like `(src/parser.rs:42)`. Disabled by default.
+///
+/// - `type_names` - Keep the generic arguments of the `Self` type and trait that the methods of an
+///   `impl` are named after, like `<Stack<T> as From<Vec<T>>>::from` instead of
+///   `<Stack as From>::from`. Disabled by default.
+///
+/// - `debug_only` - Only trace the function in builds with `debug_assertions`, so that release
+///   builds run it exactly as if it wasn't traced. Enabling the `debug-only` feature of `trace`
+///   makes every `#[trace]` `debug_only`. Disabled by default.
+///
+/// - `stats` - Collect the number of calls of each function with their duration and maximum
+///   recursion depth, which `trace::report()` prints. Disabled by default.
+///
+/// Note that `enable` and `disable` cannot be used together, and doing so will result in an error.
+///
+/// `level_enter`, `level_exit` and `target` require `logging` or `tracing`.
+///
+/// Further note that `format_enter` or `format_exit` cannot be used together with with `pretty`, and doing so will result in an error.
+#[proc_macro_attribute]
+pub fn trace(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let raw_args = syn::parse_macro_input!(args as syn::AttributeArgs);
+    let args = match args::Args::from_raw_args(raw_args) {
+        Ok(args) => args,
+        Err(errors) => {
+            return errors
+                .iter()
+                .map(syn::Error::to_compile_error)
+                .collect::<proc_macro2::TokenStream>()
+                .into()
+        }
+    };
+
+    // Items that `syn` can't represent (like trait methods without a body) are parsed as verbatim
+    // items, so those have to be tried as impl and trait items as well
+    let output = if let Ok(item) = syn::Item::parse
+        .parse(input.clone())
+        .and_then(|item| match item {
+            syn::Item::Verbatim(_) => Err(syn::Error::new(Span::call_site(), "verbatim item")),
+            _ => Ok(item),
+        }) {
+        expand_item(&args, item)
+    } else if let Ok(trait_item @ syn::TraitItem::Method(_)) =
+        syn::TraitItem::parse.parse(input.clone())
+    {
+        expand_trait_item(&args, trait_item)
+    } else if let Ok(impl_item) = syn::ImplItem::parse.parse(input.clone()) {
+        expand_impl_item(&args, impl_item)
+    } else {
+        let input2 = proc_macro2::TokenStream::from(input);
+        syn::Error::new_spanned(input2, "expected one of: `fn`, `impl`, `mod`, `trait`")
+            .to_compile_error()
+    };
+
+    output.into()
+}
+
+/// Enables tracing for all functions in a module file or crate
+///
+/// This is a stable alternative to using `#![trace]` as an inner attribute, which requires nightly.
+/// The items of the module are wrapped in the macro, and the arguments that `#[trace]` takes can be
+/// given with a `#![trace(...)]` inner attribute at the start of the macro:
+/// ```
+/// trace::trace_module! {
+///     #![trace(disable(helper))]
+///
+///     fn main() {
+///         helper(foo(1));
+///     }
+///
+///     fn foo(a: i32) -> i32 {
+///         a + 1
+///     }
+///
+///     fn helper(b: i32) {
+///         println!("{}", b);
+///     }
+/// }
+/// ```
+///
+/// The items are traced exactly as if they were in a `mod` with `#[trace]` applied to it, so each
+/// module file is traced by wrapping its own items (see `examples/example_trace_module.rs`).
+/// Out-of-line modules (`mod foo;`) have to be declared outside of the macro, as their files can't
+/// be read with the spans of their items.
+#[proc_macro]
+pub fn trace_module(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let mut file = syn::parse_macro_input!(input as syn::File);
+
+    let mut raw_args = vec![];
+    let mut errors = vec![];
+    for attr in file.attrs.drain(..) {
+        if !attr.path.is_ident("trace") {
+            errors.push(syn::Error::new_spanned(
+                attr,
+                "only `#![trace]` is supported as an inner attribute of `trace_module!`",
+            ));
+            continue;
+        }
+
+        match attr.parse_meta() {
+            Ok(syn::Meta::Path(_)) => (),
+            Ok(syn::Meta::List(meta_list)) => raw_args.extend(meta_list.nested),
+            Ok(meta @ syn::Meta::NameValue(_)) => errors.push(syn::Error::new_spanned(
+                meta,
+                "expected `#![trace]` or `#![trace(...)]`",
+            )),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    let args = match args::Args::from_raw_args(raw_args) {
+        Ok(args) if errors.is_empty() => args,
+        result => {
+            return errors
+                .into_iter()
+                .chain(result.err().into_iter().flatten())
+                .map(syn::Error::into_compile_error)
+                .collect::<proc_macro2::TokenStream>()
+                .into()
+        }
+    };
+
+    transform_mod_items(&args, AttrApplied::Directly, &mut file.items);
+
+    let items = file.items;
+    let output = quote! { #(#items)* };
+
+    output.into()
+}
+
+#[derive(Clone, Copy)]
+enum AttrApplied {
+    Directly,
+    Indirectly,
+}
+
+/// Where a traced function is defined, which decides how it's named
+#[derive(Clone, Copy)]
+enum FnContext<'a> {
+    /// A function that isn't part of an `impl` or a `trait`
+    Free,
+    /// A method that `#[trace]` was applied to directly, so its `Self` type is only known at
+    /// runtime and its trait, if any, isn't known at all
+    Associated,
+    /// A function of a traced `trait`, which is named like `<Type as Trait>::name` with `location`
+    Trait(&'a syn::Ident),
+    /// A function of a traced `impl`, which is named like `Type::name` or `<Type as Trait>::name`
+    Impl {
+        self_ty: &'a syn::Type,
+        trait_: Option<&'a syn::Path>,
+    },
+}
+
+fn expand_item(args: &args::Args, mut item: syn::Item) -> proc_macro2::TokenStream {
+    transform_item(args, AttrApplied::Directly, &mut item);
+
+    match item {
+        // An out-of-line module is turned into a verbatim item with an error
+        syn::Item::Fn(_)
+        | syn::Item::Mod(_)
+        | syn::Item::Impl(_)
+        | syn::Item::Trait(_)
+        | syn::Item::Verbatim(_) => item.into_token_stream(),
+        _ => syn::Error::new_spanned(item, "#[trace] is not supported for this item")
+            .to_compile_error(),
+    }
+}
+
+fn expand_impl_item(args: &args::Args, mut impl_item: syn::ImplItem) -> proc_macro2::TokenStream {
+    transform_impl_item(args, AttrApplied::Directly, &mut impl_item);
+
+    match impl_item {
+        syn::ImplItem::Method(_) => impl_item.into_token_stream(),
+        _ => syn::Error::new_spanned(impl_item, "#[trace] is not supported for this impl item")
+            .to_compile_error(),
+    }
+}
+
+fn expand_trait_item(
+    args: &args::Args,
+    mut trait_item: syn::TraitItem,
+) -> proc_macro2::TokenStream {
+    transform_trait_item(args, AttrApplied::Directly, &mut trait_item);
+
+    match trait_item {
+        syn::TraitItem::Method(syn::TraitItemMethod {
+            default: Some(_), ..
+        }) => trait_item.into_token_stream(),
+        syn::TraitItem::Method(_) => syn::Error::new_spanned(
+            trait_item,
+            "#[trace] is only supported for trait methods with a default body",
+        )
+        .to_compile_error(),
+        _ => syn::Error::new_spanned(trait_item, "#[trace] is not supported for this trait item")
+            .to_compile_error(),
+    }
+}
+
+fn transform_item(args: &args::Args, attr_applied: AttrApplied, item: &mut syn::Item) {
+    match *item {
+        syn::Item::Fn(ref mut item_fn) => transform_fn(args, attr_applied, item_fn),
+        syn::Item::Mod(ref mut item_mod) => {
+            // The declaration is kept next to the error, so that the paths into the module still
+            // resolve and only the error is reported
+            if let Err(e) = transform_mod(args, attr_applied, item_mod) {
+                let error = e.into_compile_error();
+                *item = syn::Item::Verbatim(quote! { #item_mod #error });
+            }
+        }
+        syn::Item::Impl(ref mut item_impl) => transform_impl(args, attr_applied, item_impl),
+        syn::Item::Trait(ref mut item_trait) => transform_trait(args, attr_applied, item_trait),
+        _ => (),
+    }
+}
+
+fn transform_fn(args: &args::Args, attr_applied: AttrApplied, item_fn: &mut syn::ItemFn) {
+    // Methods with a body that `#[trace]` is applied to directly are parsed as functions as well,
+    // so the ones that take `self` or mention `Self` are told apart by their signature
+    let fn_context = match attr_applied {
+        AttrApplied::Directly if mentions_self(&item_fn.sig) => FnContext::Associated,
+        _ => FnContext::Free,
+    };
+
+    *item_fn.block =
+        construct_traced_block(args, attr_applied, fn_context, &item_fn.sig, &item_fn.block);
+}
+
+fn transform_mod(
+    args: &args::Args,
+    attr_applied: AttrApplied,
+    item_mod: &mut syn::ItemMod,
+) -> syn::Result<()> {
+    assert!(
+        (item_mod.content.is_some() && item_mod.semi.is_none())
+            || (item_mod.content.is_none() && item_mod.semi.is_some())
+    );
+
+    // The items of an out-of-line module are in a file that a macro can only read as tokens without
+    // their real spans, which would make panics, `file!()` and debug info point to the declaration
+    // instead of the file
+    let Some((_, ref mut items)) = item_mod.content else {
+        return Err(syn::Error::new_spanned(
+            &item_mod,
+            format!(
+                "#[trace] can't trace the items of an out-of-line module, declare `mod {};` \
+                 outside of the traced items and wrap the items in its file in \
+                 `trace::trace_module! {{ ... }}` instead",
+                item_mod.ident
+            ),
+        ));
+    };
+    transform_mod_items(args, attr_applied, items);
+
+    Ok(())
+}
+
+fn transform_mod_items(args: &args::Args, attr_applied: AttrApplied, items: &mut [syn::Item]) {
+    items.iter_mut().for_each(|item| {
+        if let AttrApplied::Directly = attr_applied {
+            match *item {
+                syn::Item::Fn(syn::ItemFn {
+                    sig: syn::Signature { ref ident, .. },
+                    ..
+                })
+                | syn::Item::Mod(syn::ItemMod { ref ident, .. }) => match args.filter {
+                    args::Filter::Enable(ref idents) if !idents.contains(ident) => {
+                        return;
+                    }
+                    args::Filter::Disable(ref idents) if idents.contains(ident) => {
+                        return;
+                    }
+                    _ => (),
+                },
+                _ => (),
+            }
+        }
+
+        transform_item(args, AttrApplied::Indirectly, item);
+    });
+}
+
+fn transform_impl(args: &args::Args, attr_applied: AttrApplied, item_impl: &mut syn::ItemImpl) {
+    let syn::ItemImpl {
+        ref self_ty,
+        ref trait_,
+        ref mut items,
+        ..
+    } = *item_impl;
+    let fn_context = FnContext::Impl {
+        self_ty,
+        trait_: trait_.as_ref().map(|(_, path, _)| path),
+    };
+
+    items.iter_mut().for_each(|impl_item| {
+        if let syn::ImplItem::Method(ref mut impl_item_method) = *impl_item {
+            if let AttrApplied::Directly = attr_applied {
+                let ident = &impl_item_method.sig.ident;
+
+                match args.filter {
+                    args::Filter::Enable(ref idents) if !idents.contains(ident) => {
+                        return;
+                    }
+                    args::Filter::Disable(ref idents) if idents.contains(ident) => {
+                        return;
+                    }
+                    _ => (),
+                }
+            }
+
+            impl_item_method.block = construct_traced_block(
+                args,
+                AttrApplied::Indirectly,
+                fn_context,
+                &impl_item_method.sig,
+                &impl_item_method.block,
+            );
+        }
+    });
+}
+
+fn transform_trait(args: &args::Args, attr_applied: AttrApplied, item_trait: &mut syn::ItemTrait) {
+    let fn_context = FnContext::Trait(&item_trait.ident);
+
+    item_trait.items.iter_mut().for_each(|trait_item| {
+        if let syn::TraitItem::Method(ref mut trait_item_method) = *trait_item {
+            if let AttrApplied::Directly = attr_applied {
+                let ident = &trait_item_method.sig.ident;
+
+                match args.filter {
+                    args::Filter::Enable(ref idents) if !idents.contains(ident) => {
+                        return;
+                    }
+                    args::Filter::Disable(ref idents) if idents.contains(ident) => {
+                        return;
+                    }
+                    _ => (),
+                }
+            }
+
+            transform_trait_method(args, AttrApplied::Indirectly, fn_context, trait_item_method);
+        }
+    });
+}
+
+fn transform_trait_item(
+    args: &args::Args,
+    attr_applied: AttrApplied,
+    trait_item: &mut syn::TraitItem,
+) {
+    // Will probably add more cases in the future
+    #[allow(clippy::single_match)]
+    match *trait_item {
+        syn::TraitItem::Method(ref mut trait_item_method) => {
+            transform_trait_method(args, attr_applied, FnContext::Associated, trait_item_method)
+        }
+        _ => (),
+    }
+}
+
+fn transform_trait_method(
+    args: &args::Args,
+    attr_applied: AttrApplied,
+    fn_context: FnContext,
+    trait_item_method: &mut syn::TraitItemMethod,
+) {
+    // Methods without a default body have nothing to trace
+    if let Some(ref mut block) = trait_item_method.default {
+        *block = construct_traced_block(
+            args,
+            attr_applied,
+            fn_context,
+            &trait_item_method.sig,
+            block,
+        );
+    }
+}
+
+fn transform_impl_item(
+    args: &args::Args,
+    attr_applied: AttrApplied,
+    impl_item: &mut syn::ImplItem,
+) {
+    // Will probably add more cases in the future
+    #[allow(clippy::single_match)]
+    match *impl_item {
+        syn::ImplItem::Method(ref mut impl_item_method) => {
+            transform_method(args, attr_applied, impl_item_method)
+        }
+        _ => (),
+    }
+}
+
+fn transform_method(
+    args: &args::Args,
+    attr_applied: AttrApplied,
+    impl_item_method: &mut syn::ImplItemMethod,
+) {
+    impl_item_method.block = construct_traced_block(
+        args,
+        attr_applied,
+        FnContext::Associated,
+        &impl_item_method.sig,
+        &impl_item_method.block,
+    );
+}
+
+fn construct_traced_block(
+    args: &args::Args,
+    attr_applied: AttrApplied,
+    fn_context: FnContext,
+    sig: &syn::Signature,
+    original_block: &syn::Block,
+) -> syn::Block {
+    let traced_block =
+        match construct_instrumented_block(args, attr_applied, fn_context, sig, original_block) {
+            Ok(traced_block) => traced_block,
+            Err(e) => {
+                let error = e.into_compile_error();
+                return parse_quote! {{#error}};
+            }
+        };
+
+    // With `debug_only`, release builds run the original block as if it wasn't traced. It's not
+    // wrapped in a closure there, so an early `return` or a `?` leaves the function as usual.
+    if args.debug_only {
+        let return_type = return_type_annotation(sig);
+        // The arguments that are only printed would be unused otherwise
+        let arg_idents = extract_arg_idents(args, attr_applied, sig)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|arg_ident| match arg_ident {
+                ArgIdent::Ident(ident) => Some(ident),
+                ArgIdent::Ignored => None,
+            });
+        parse_quote! {{
+            #[cfg(debug_assertions)]
+            let fn_return_value #return_type = #traced_block;
+            #[cfg(not(debug_assertions))]
+            let fn_return_value #return_type = {
+                let _ = (#(&#arg_idents,)*);
+                #original_block
+            };
+            fn_return_value
+        }}
+    } else {
+        traced_block
+    }
+}
+
+/// Constructs the block of a traced function, which reports its calls
+fn construct_instrumented_block(
+    args: &args::Args,
+    attr_applied: AttrApplied,
+    fn_context: FnContext,
+    sig: &syn::Signature,
+    original_block: &syn::Block,
+) -> syn::Result<syn::Block> {
+    let arg_idents = extract_arg_idents(args, attr_applied, sig)?;
+    let name = qualified_name(args, fn_context, &sig.ident);
+    if args.tracing {
+        return Ok(construct_tracing_block(
+            args,
+            &name,
+            sig,
+            &arg_idents,
+            original_block,
+        ));
+    }
+
+    // The values of `{thread}` and `{time}` when they're interpolated, which aren't arguments
+    let timestamp = match args.timestamp {
+        Some(args::Timestamp::Wall) | None => quote! { ::trace::__private::Timestamp::Wall },
+        Some(args::Timestamp::SinceFirstCall) => {
+            quote! { ::trace::__private::Timestamp::SinceFirstCall }
+        }
+    };
+    let placeholder_value = |placeholder: &TokenStream| match placeholder.to_string().as_str() {
+        "thread" => quote! { ::trace::__private::CurrentThread },
+        "time" => timestamp.clone(),
+        _ => placeholder.clone(),
+    };
+
+    // `enter_val` are the values printed when the function is entered, while `arg_idents` are the
+    // arguments among them
+    let (enter_format, enter_val, arg_idents) = if let Some(fmt_str) = &args.format_enter {
+        let arg_idents = arg_idents
+            .iter()
+            .filter_map(|arg_ident| match *arg_ident {
+                ArgIdent::Ident(ref ident) => Some(ident.to_token_stream()),
+                ArgIdent::Ignored => None,
+            })
+            .collect::<Vec<_>>();
+        // Arguments named like a placeholder take precedence over it
+        let placeholders = ["thread", "time"]
+            .into_iter()
+            .filter(|placeholder| !arg_idents.iter().any(|arg| arg.to_string() == *placeholder))
+            .map(|placeholder| syn::Ident::new(placeholder, Span::call_site()).to_token_stream())
+            .collect::<Vec<_>>();
+        let (enter_format, kept) = parse_fmt_str(
+            fmt_str,
+            arg_idents.iter().chain(&placeholders).cloned().collect(),
+        );
+        let enter_val = kept
+            .iter()
+            .map(|val| {
+                if arg_idents.contains_token_stream(val) {
+                    val.clone()
+                } else {
+                    placeholder_value(val)
+                }
+            })
+            .collect();
+        let arg_idents = kept
+            .into_iter()
+            .filter(|val| arg_idents.contains_token_stream(val))
+            .collect();
+        (enter_format, enter_val, arg_idents)
+    } else {
+        let mut idents = vec![];
+        let enter_format = arg_idents
+            .iter()
+            .map(|arg_ident| match *arg_ident {
+                ArgIdent::Ident(ref ident) => {
+                    idents.push(ident.to_token_stream());
+                    format!("{} = {{:?}}", ident)
+                }
+                ArgIdent::Ignored => "_ = <ignored>".to_owned(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        (Ok(enter_format), idents.clone(), idents)
+    };
+    // we set set exit val to be a vector with one element which is Ident called r
+    // this means that the format parser can indentify when then return value should be interprolated
+    // so if we want to use a different symbol to denote return value interpolation we just need to change the symbol in the following quote
+    // ie: `let exit_val = vec![quote!(return_value)];` if we wanted to use return_value to denote return value interpolation
+    // The same goes for `elapsed`, the duration of the call, when it's measured
+    let mut exit_val = vec![quote!(r)];
+    if args.timing {
+        exit_val.push(quote!(elapsed));
+    }
+    let (exit_format, exit_val) = if let Some(fmt_str) = &args.format_exit {
+        exit_val.extend([quote!(thread), quote!(time)]);
+        parse_fmt_str(fmt_str, exit_val)
+    } else {
+        let return_format = if args.pretty { "{:#?}" } else { "{:?}" };
+        if args.timing {
+            (Ok(format!("{} ({{}})", return_format)), exit_val)
+        } else {
+            (Ok(return_format.to_string()), exit_val)
+        }
+    };
+    let should_interpolate = exit_val.iter().any(|val| val.to_string() == "r");
+    let enter_format = enter_format?;
+
+    // The timestamp and the thread come before the indentation, so that they line up. `line_val`
+    // are the named values that every line is formatted with.
+    let mut line_prefix = String::new();
+    let mut line_val = vec![];
+    if args.timestamp.is_some() {
+        line_prefix.push_str("{trace_time} ");
+        line_val.push(quote! { trace_time = #timestamp });
+    }
+    if args.thread {
+        line_prefix.push_str("[{trace_thread}] ");
+        line_val.push(quote! { trace_thread = ::trace::__private::CurrentThread });
+    }
+    // With `location`, the function is named by its path, and the Entering line ends with where
+    // it's defined. `line!()` takes the line of the span it's given.
+    let line = quote_spanned! {sig.ident.span()=> ::std::line!() };
+    let (fn_name, location_format, location_val) = if args.location {
+        let ident = sig.ident.to_string();
+        let path = match fn_context {
+            FnContext::Free => quote! { ::std::concat!(::std::module_path!(), "::", #ident) },
+            FnContext::Associated | FnContext::Impl { trait_: None, .. } => quote! {
+                ::std::format_args!("{}::{}", ::std::any::type_name::<Self>(), #ident)
+            },
+            FnContext::Trait(trait_ident) => {
+                let trait_name = trait_ident.to_string();
+                quote! {
+                    ::std::format_args!(
+                        "<{} as {}>::{}",
+                        ::std::any::type_name::<Self>(),
+                        #trait_name,
+                        #ident,
+                    )
+                }
+            }
+            FnContext::Impl {
+                trait_: Some(trait_),
+                ..
+            } => {
+                let trait_name = trait_name(args, trait_);
+                quote! {
+                    ::std::format_args!(
+                        "<{} as {}>::{}",
+                        ::std::any::type_name::<Self>(),
+                        #trait_name,
+                        #ident,
+                    )
+                }
+            }
+        };
+        line_val.push(quote! { trace_fn = #path });
+        (
+            "{trace_fn}".to_owned(),
+            " ({trace_location})",
+            Some(quote! { trace_location = ::std::concat!(::std::file!(), ":", #line), }),
+        )
+    } else {
+        // The name is part of the format strings, and the type of a const generic argument may
+        // contain braces
+        (name.replace('{', "{{").replace('}', "}}"), "", None)
+    };
+
+    let entering_format = format!(
+        "{}{{:depth$}}{} Entering {}({}){}",
+        line_prefix, args.prefix_enter, fn_name, enter_format, location_format
+    );
+    let exiting_format = format!(
+        "{}{{:depth$}}{} Exiting {} = {}",
+        line_prefix, args.prefix_exit, fn_name, exit_format?
+    );
+
+    let pause_stmt = if args.pause {
+        quote! { ::trace::__private::pause(); }
+    } else {
+        quote!()
+    };
+
+    let printer = if args.logging {
+        quote! { ::trace::__private::log::log! }
+    } else {
+        quote! { ::trace::__private::println! }
+    };
+    // What's passed to the printer before the format string: the level (and target) of each line
+    // when logging, or where to print it otherwise
+    let (enter_options, exit_options) = if args.logging {
+        let target = args
+            .target
+            .as_ref()
+            .map(|target| quote! { target: #target, });
+        let enter_level = log_level(args.level_enter);
+        let exit_level = log_level(args.level_exit);
+        (
+            quote! { #target #enter_level, },
+            quote! { #target #exit_level, },
+        )
+    } else {
+        let output = match args.output {
+            args::Output::Stdout => quote!(Stdout),
+            args::Output::Stderr => quote!(Stderr),
+        };
+        (quote! { #output, }, quote! { #output, })
+    };
+    let panicking_format = format!(
+        "{}{{:depth$}}{} Panicked in {}",
+        line_prefix, args.prefix_panic, fn_name
+    );
+    let panicking_payload_format = format!("{}: {{}}", panicking_format);
+
+    let return_type = return_type_annotation(sig);
+    // The original block is nested inside these bindings' scope, so they must not shadow an
+    // argument
+    let depth = syn::Ident::new("depth", Span::mixed_site());
+    let sink = syn::Ident::new("sink", Span::mixed_site());
+    let callsite = syn::Ident::new("callsite", Span::mixed_site());
+    let traced = syn::Ident::new("traced", Span::mixed_site());
+    let start = syn::Ident::new("start", Span::mixed_site());
+    let elapsed = syn::Ident::new("elapsed", Span::mixed_site());
+
+    // The same values are sent to a `TraceSink` instead when one is installed
+    let debug_format = if args.pretty { "{:#?}" } else { "{:?}" };
+    let sink_args = arg_idents.iter().map(|arg_ident| {
+        let arg_name = arg_ident.to_string();
+        quote! { (#arg_name, ::std::format!(#debug_format, #arg_ident)) }
+    });
+    let sink_return_value = if should_interpolate {
+        quote! { Some(&*::std::format!(#debug_format, fn_return_value)) }
+    } else {
+        quote!(None)
+    };
+
+    // Run the original body as its own closure (or async block) so that an early `return` or a `?`
+    // only leaves the body and still reaches the exit line below. The body is run under
+    // `catch_unwind` so that a panic restores `DEPTH` and is reported before it keeps unwinding.
+    let (run_body, set_depth_enter, set_depth_exit) = if sig.asyncness.is_some() {
+        // The arguments are repeated on the suspended and resumed lines, as that's the only way to
+        // tell apart multiple calls of the same function that are polled concurrently
+        let on_poll_event = if args.poll_events {
+            let suspended_format =
+                format!("{}{{:depth$}}[~] Suspended {}({{}})", line_prefix, fn_name);
+            let resumed_format = format!("{}{{:depth$}}[~] Resumed {}({{}})", line_prefix, fn_name);
+
+            // These aren't reported to a `TraceSink`
+            quote! {{
+                let args = (#traced && #sink.is_none())
+                    .then(|| ::std::format!(#enter_format, #(#enter_val,)*));
+                move |event| {
+                    if let Some(ref args) = args {
+                        match event {
+                            ::trace::__private::PollEvent::Suspended => {
+                                #printer(
+                                    #exit_options #suspended_format,
+                                    "",
+                                    args,
+                                    #(#line_val,)*
+                                    depth = #depth
+                                )
+                            }
+                            ::trace::__private::PollEvent::Resumed => {
+                                #printer(
+                                    #enter_options #resumed_format,
+                                    "",
+                                    args,
+                                    #(#line_val,)*
+                                    depth = #depth
+                                )
+                            }
+                        }
+                    }
+                }
+            }}
+        } else {
+            quote! { |_| () }
+        };
+        let run_body = quote! {
+            ::trace::__private::TracedFuture::new(
+                async move #original_block,
+                if #traced { #depth + 1 } else { #depth },
+                #on_poll_event,
+            )
+            .await
+        };
+
+        (run_body, quote!(), quote!())
+    } else {
+        let run_body = quote! { ::trace::__private::catch_unwind(move || #original_block) };
+
+        (
+            run_body,
+            quote! {
+                if #traced {
+                    ::trace::__private::DEPTH.with(|d| d.set(#depth + 1));
+                }
+            },
+            quote! { ::trace::__private::DEPTH.with(|d| d.set(#depth)); },
+        )
+    };
+
+    // The calls are timed for `stats` as well, but then the duration isn't printed
+    let (start_timer, stop_timer) = if args.timing || args.stats {
+        (
+            quote! { let #start = ::std::time::Instant::now(); },
+            quote! { let #elapsed = #start.elapsed(); },
+        )
+    } else {
+        (quote!(), quote!())
+    };
+    let sink_elapsed = if args.timing {
+        quote! { Some(#elapsed) }
+    } else {
+        quote!(None)
+    };
+    let (stats_enter, stats_exit, stats_panic) = if args.stats {
+        // The methods whose `Self` type is only known at runtime are told apart by it
+        let stats_fn = match fn_context {
+            FnContext::Free | FnContext::Impl { .. } => quote! { None, None },
+            FnContext::Associated => quote! { Some(::std::any::type_name::<Self>()), None },
+            FnContext::Trait(trait_ident) => {
+                let trait_name = trait_ident.to_string();
+                quote! { Some(::std::any::type_name::<Self>()), Some(#trait_name) }
+            }
+        };
+        (
+            quote! {
+                if #traced {
+                    ::trace::__private::stats_enter(#callsite, #stats_fn);
+                }
+            },
+            quote! {
+                if #traced {
+                    ::trace::__private::stats_exit(#callsite, #stats_fn, #elapsed);
+                }
+            },
+            quote! {
+                if #traced {
+                    ::trace::__private::stats_exit(#callsite, #stats_fn, #start.elapsed());
+                }
+            },
+        )
+    } else {
+        (quote!(), quote!(), quote!())
+    };
+    let exit_val = exit_val
+        .iter()
+        .map(|val| match val.to_string().as_str() {
+            "r" => quote!(fn_return_value),
+            "elapsed" => quote! { ::trace::__private::Elapsed(#elapsed) },
+            _ => placeholder_value(val),
+        })
+        .collect::<Vec<_>>();
+    let print_exit = quote! {{
+        #printer(
+            #exit_options #exiting_format,
+            "",
+            #(#exit_val,)*
+            #(#line_val,)*
+            depth = #depth
+        );
+    }};
+    // Calls made while tracing is off, or of functions filtered out by `RUST_TRACE`, don't report
+    // anything or change the depth
+    Ok(parse_quote! {{
+        static __TRACE_CALLSITE: ::trace::__private::Callsite = ::trace::__private::Callsite::new(
+            #name,
+            ::std::module_path!(),
+            ::std::file!(),
+            #line,
+        );
+        let #callsite = &__TRACE_CALLSITE;
+        let #traced = ::trace::__private::is_enabled() && #callsite.is_traced();
+        let #depth = ::trace::__private::DEPTH.with(|d| d.get());
+        let #sink = if #traced {
+            ::trace::__private::sink()
+        } else {
+            None
+        };
+        if #traced {
+            match #sink {
+                Some(ref #sink) => ::trace::__private::on_enter(
+                    &**#sink,
+                    #callsite,
+                    #depth,
+                    &[#(#sink_args,)*],
+                ),
+                None => #printer(
+                    #enter_options #entering_format,
+                    "",
+                    #(#enter_val,)*
+                    #(#line_val,)*
+                    #location_val
+                    depth = #depth
+                ),
+            }
+            #pause_stmt
+        }
+        #set_depth_enter
+        #start_timer
+        #stats_enter
+        let fn_return_value #return_type = match #run_body {
+            Ok(fn_return_value) => fn_return_value,
+            Err(payload) => {
+                #stats_panic
+                #set_depth_exit
+                if #traced {
+                    let message = ::trace::__private::panic_message(&*payload);
+                    match (&#sink, message) {
+                        (Some(#sink), message) => ::trace::__private::on_panic(
+                            &**#sink,
+                            #callsite,
+                            #depth,
+                            message,
+                        ),
+                        (None, Some(message)) => #printer(
+                            #exit_options #panicking_payload_format,
+                            "",
+                            message,
+                            #(#line_val,)*
+                            depth = #depth
+                        ),
+                        (None, None) => #printer(
+                            #exit_options #panicking_format,
+                            "",
+                            #(#line_val,)*
+                            depth = #depth
+                        ),
+                    }
+                    #pause_stmt
+                }
+                ::std::panic::resume_unwind(payload)
+            }
+        };
+        #stop_timer
+        #stats_exit
+        #set_depth_exit
+        if #traced {
+            match #sink {
+                Some(ref #sink) => ::trace::__private::on_exit(
+                    &**#sink,
+                    #callsite,
+                    #depth,
+                    #sink_return_value,
+                    #sink_elapsed,
+                ),
+                None => #print_exit
+            }
+            #pause_stmt
+        }
+        fn_return_value
+    }})
+}
+
+/// Constructs the block of a function traced with the `tracing` crate
+///
+/// Each call opens a span named after the function, with the arguments as fields and a
+/// `return_value` field that is recorded when the call returns. The subscriber takes care of the
+/// nesting, so `DEPTH` is left alone.
+fn construct_tracing_block(
+    args: &args::Args,
+    name: &str,
+    sig: &syn::Signature,
+    arg_idents: &[ArgIdent],
+    original_block: &syn::Block,
+) -> syn::Block {
+    // `_` arguments don't have a name that could be used as a field
+    let fields = arg_idents.iter().filter_map(|arg_ident| match *arg_ident {
+        ArgIdent::Ident(ref ident) => Some(quote! { #ident = ?#ident }),
+        ArgIdent::Ignored => None,
+    });
+
+    let pause_stmt = if args.pause {
+        quote! { ::trace::__private::pause(); }
+    } else {
+        quote!()
+    };
+
+    let target = args
+        .target
+        .as_ref()
+        .map(|target| quote! { target: #target, });
+    let enter_level = tracing_level(args.level_enter);
+    let exit_level = tracing_level(args.level_exit);
+
+    let return_type = return_type_annotation(sig);
+    // The original block is nested inside these bindings' scope, so they must not shadow an
+    // argument
+    let span = syn::Ident::new("span", Span::mixed_site());
+    let entered = syn::Ident::new("entered", Span::mixed_site());
+    let traced = syn::Ident::new("traced", Span::mixed_site());
+
+    let run_body = if sig.asyncness.is_some() {
+        quote! {
+            ::trace::__private::TracedFuture::new(
+                ::trace::__private::tracing::Instrument::instrument(
+                    async move #original_block,
+                    ::std::clone::Clone::clone(&#span),
+                ),
+                ::trace::__private::DEPTH.with(|d| d.get()),
+                |_| (),
+            )
+            .await
+        }
+    } else {
+        quote! {{
+            let #entered = #span.enter();
+            ::trace::__private::catch_unwind(move || #original_block)
+        }}
+    };
+
+    // No span is opened while tracing is off. Without the `tracing` feature of `trace`,
+    // `if_tracing!` replaces all of this with an error naming the feature
+    parse_quote! {{ ::trace::__private::if_tracing!({
+        let #traced = ::trace::__private::is_enabled();
+        let #span = if #traced {
+            ::trace::__private::tracing::span!(
+                #target
+                #enter_level,
+                #name,
+                #(#fields,)*
+                return_value = ::trace::__private::tracing::field::Empty,
+            )
+        } else {
+            ::trace::__private::tracing::Span::none()
+        };
+        if #traced {
+            #pause_stmt
+        }
+        let fn_return_value #return_type = match #run_body {
+            Ok(fn_return_value) => fn_return_value,
+            Err(payload) => {
+                if #traced {
+                    match ::trace::__private::panic_message(&*payload) {
+                        Some(message) => ::trace::__private::tracing::event!(
+                            #target
+                            parent: &#span,
+                            #exit_level,
+                            panic = message,
+                            "panicked",
+                        ),
+                        None => ::trace::__private::tracing::event!(
+                            #target
+                            parent: &#span,
+                            #exit_level,
+                            "panicked",
+                        ),
+                    }
+                    #pause_stmt
+                }
+                ::std::panic::resume_unwind(payload)
+            }
+        };
+        if #traced {
+            #span.record(
+                "return_value",
+                ::trace::__private::tracing::field::debug(&fn_return_value),
+            );
+            #pause_stmt
+        }
+        fn_return_value
+    }) }}
+}
+
+fn log_level(level: args::Level) -> TokenStream {
+    match level {
+        args::Level::Error => quote! { ::trace::__private::log::Level::Error },
+        args::Level::Warn => quote! { ::trace::__private::log::Level::Warn },
+        args::Level::Info => quote! { ::trace::__private::log::Level::Info },
+        args::Level::Debug => quote! { ::trace::__private::log::Level::Debug },
+        args::Level::Trace => quote! { ::trace::__private::log::Level::Trace },
+    }
+}
+
+fn tracing_level(level: args::Level) -> TokenStream {
+    match level {
+        args::Level::Error => quote! { ::trace::__private::tracing::Level::ERROR },
+        args::Level::Warn => quote! { ::trace::__private::tracing::Level::WARN },
+        args::Level::Info => quote! { ::trace::__private::tracing::Level::INFO },
+        args::Level::Debug => quote! { ::trace::__private::tracing::Level::DEBUG },
+        args::Level::Trace => quote! { ::trace::__private::tracing::Level::TRACE },
+    }
+}
+
+/// Returns the name of a function as it's printed, qualified with its `Self` type and trait in a
+/// traced `impl`
+fn qualified_name(args: &args::Args, fn_context: FnContext, ident: &syn::Ident) -> String {
+    match fn_context {
+        FnContext::Free | FnContext::Associated | FnContext::Trait(_) => ident.to_string(),
+        FnContext::Impl {
+            self_ty,
+            trait_: None,
+        } => format!("{}::{}", type_name(args, self_ty), ident),
+        FnContext::Impl {
+            self_ty,
+            trait_: Some(trait_),
+        } => format!(
+            "<{} as {}>::{}",
+            type_name(args, self_ty),
+            trait_name(args, trait_),
+            ident
+        ),
+    }
+}
+
+/// Returns whether a function takes `self` or mentions `Self` in its signature, which only a method
+/// can do
+fn mentions_self(sig: &syn::Signature) -> bool {
+    fn contains_self_type(tokens: TokenStream) -> bool {
+        tokens.into_iter().any(|token| match token {
+            proc_macro2::TokenTree::Ident(ident) => ident == "Self",
+            proc_macro2::TokenTree::Group(group) => contains_self_type(group.stream()),
+            _ => false,
+        })
+    }
+
+    sig.receiver().is_some() || contains_self_type(sig.to_token_stream())
+}
+
+/// Returns a type as it's written, like `Wrapper<&'a str>`. Its generic arguments are left out
+/// unless `type_names` is given.
+fn type_name(args: &args::Args, ty: &syn::Type) -> String {
+    let mut ty = ty.clone();
+    if !args.type_names {
+        StripGenerics.visit_type_mut(&mut ty);
+    }
+
+    tokens_to_string(ty.into_token_stream())
+}
+
+/// Returns a trait as it's written, like `From<u32>`, see `type_name`
+fn trait_name(args: &args::Args, trait_: &syn::Path) -> String {
+    let mut trait_ = trait_.clone();
+    if !args.type_names {
+        StripGenerics.visit_path_mut(&mut trait_);
+    }
+
+    tokens_to_string(trait_.into_token_stream())
+}
+
+/// Removes the generic arguments of every path, so that `Wrapper<T>` becomes `Wrapper`
+struct StripGenerics;
+
+impl VisitMut for StripGenerics {
+    fn visit_path_arguments_mut(&mut self, arguments: &mut syn::PathArguments) {
+        if let syn::PathArguments::AngleBracketed(_) = *arguments {
+            *arguments = syn::PathArguments::None;
+        }
+    }
+}
+
+/// Writes tokens without the spaces that `TokenStream::to_string` puts between all of them
+fn tokens_to_string(tokens: TokenStream) -> String {
+    let mut string = String::new();
+    // Only words have to be separated, as in `dyn Trait` or `&'a mut T`
+    let mut after_word = false;
+    for tt in tokens {
+        match tt {
+            proc_macro2::TokenTree::Ident(_) | proc_macro2::TokenTree::Literal(_) => {
+                if after_word {
+                    string.push(' ');
+                }
+                string.push_str(&tt.to_string());
+                after_word = true;
+            }
+            proc_macro2::TokenTree::Punct(ref punct) => {
+                match punct.as_char() {
+                    ',' | ';' => {
+                        string.push(punct.as_char());
+                        string.push(' ');
+                    }
+                    '+' | '=' => {
+                        string.push(' ');
+                        string.push(punct.as_char());
+                        string.push(' ');
+                    }
+                    c => string.push(c),
+                }
+                after_word = false;
+            }
+            proc_macro2::TokenTree::Group(ref group) => {
+                let (open, close) = match group.delimiter() {
+                    proc_macro2::Delimiter::Parenthesis => ("(", ")"),
+                    proc_macro2::Delimiter::Bracket => ("[", "]"),
+                    proc_macro2::Delimiter::Brace => ("{", "}"),
+                    proc_macro2::Delimiter::None => ("", ""),
+                };
+                string.push_str(open);
+                string.push_str(&tokens_to_string(group.stream()));
+                string.push_str(close);
+                after_word = false;
+            }
+        }
+    }
+
+    string
+}
+
+/// Returns the type annotation for the return value binding, which is needed for `?` to infer its
+/// error conversion. `impl Trait` types can't be written in a `let`, so they're left to inference,
+/// and so is `!`, which can't be written outside of a return type on stable.
+fn return_type_annotation(sig: &syn::Signature) -> TokenStream {
+    fn contains_impl(tokens: TokenStream) -> bool {
+        tokens.into_iter().any(|tt| match tt {
+            proc_macro2::TokenTree::Ident(ref ident) => ident == "impl",
+            proc_macro2::TokenTree::Group(ref group) => contains_impl(group.stream()),
+            _ => false,
+        })
+    }
+
+    match sig.output {
+        syn::ReturnType::Type(_, ref ty)
+            if !matches!(**ty, syn::Type::Never(_)) && !contains_impl(ty.to_token_stream()) =>
+        {
+            quote!(: #ty)
+        }
+        syn::ReturnType::Type(..) => quote!(),
+        syn::ReturnType::Default => quote!(: ()),
+    }
+}
+
+fn parse_fmt_str(
+    fmt_str: &str,
+    mut arg_idents: Vec<TokenStream>,
+) -> (Result<String, syn::Error>, Vec<TokenStream>) {
+    let mut fixed_format_str = String::new();
+    let mut kept_arg_idents = Vec::new();
+    let mut fmt_iter = fmt_str.chars().peekable();
+    while let Some(fmt_char) = fmt_iter.next() {
+        match fmt_char {
+            '{' => {
+                if let Some('{') = fmt_iter.peek() {
+                    fixed_format_str.push_str("{{");
+                    fmt_iter.next();
+                } else {
+                    match parse_interpolated(&mut fmt_iter, &mut arg_idents, &mut kept_arg_idents) {
+                        Ok(interpolated) => fixed_format_str.push_str(&interpolated),
+                        Err(e) => return (Err(e), kept_arg_idents),
+                    }
+                }
+            }
+            '}' => {
+                if fmt_iter.next() != Some('}') {
+                    return (Err(syn::Error::new(
+                            Span::call_site(),
+                            "invalid format string: unmatched `}` found\nif you intended to print `}`, you can escape it using `}}`"
+                        )), kept_arg_idents);
+                }
+
+                fixed_format_str.push_str("}}")
+            }
+            _ => fixed_format_str.push(fmt_char),
+        }
+    }
+    (Ok(fixed_format_str), kept_arg_idents)
+}
+
+fn fix_interpolated(
+    last_char: char,
+    ident: String,
+    arg_idents: &mut Vec<TokenStream>,
+    kept_arg_idents: &mut Vec<TokenStream>,
+) -> Result<String, syn::Error> {
+    if last_char != '}' {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "invalid format string: expected `'}}'` but string was terminated\nif you intended to print `{{`, you can escape it using `{{`.",
+        ));
+    }
+    let predicate = |arg_ident: &TokenStream| arg_ident.to_string() == ident;
+    if let Some(index) = kept_arg_idents.iter().position(predicate) {
+        Ok(format!("{{{}}}", index + 1))
+    } else if let Some(index) = arg_idents.iter().position(predicate) {
+        kept_arg_idents.push(arg_idents.remove(index));
+        Ok(format!("{{{}}}", kept_arg_idents.len()))
+    } else {
+        Err(syn::Error::new(
+            Span::call_site(),
+            // TODO: better error message
+            format!("cannot find `{ident}` in this scope."),
+        ))
+    }
+}
+
+fn parse_interpolated(
+    fmt_iter: &mut Peekable<Chars>,
+    arg_idents: &mut Vec<TokenStream>,
+    kept_arg_idents: &mut Vec<TokenStream>,
+) -> Result<String, syn::Error> {
+    let mut last_char = ' ';
+    let mut ident = String::new();
+    while let Some(ident_char) = fmt_iter.next() {
+        match ident_char {
+            '}' => {
+                last_char = '}';
+                break;
+            }
+            _ => {
+                last_char = ident_char;
+                if !ident_char.is_whitespace() {
+                    ident.push(ident_char);
+                } else {
+                    skip_whitespace_and_check(fmt_iter, &mut last_char, ident_char)?;
+                }
+            }
+        }
+    }
+    fix_interpolated(last_char, ident, arg_idents, kept_arg_idents)
+}
+
+fn skip_whitespace_and_check(
+    fmt_iter: &mut Peekable<Chars>,
+    last_char: &mut char,
+    ident_char: char,
+) -> Result<(), syn::Error> {
+    for blank_char in fmt_iter.by_ref() {
+        match blank_char {
+            '}' => {
+                *last_char = '}';
+                break;
+            }
+            c if c.is_whitespace() => {
+                *last_char = ident_char;
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    format!("invalid format string: expected `'}}'`, found `'{blank_char}'`\nif you intended to print `{{`, you can escape it using `{{`."),
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+trait ContainsTokenStream {
+    fn contains_token_stream(&self, tokens: &TokenStream) -> bool;
+}
+
+impl ContainsTokenStream for [TokenStream] {
+    fn contains_token_stream(&self, tokens: &TokenStream) -> bool {
+        let tokens = tokens.to_string();
+        self.iter().any(|elem| elem.to_string() == tokens)
+    }
+}
+
+/// An argument binding as it's printed when a function is entered
+enum ArgIdent {
+    Ident(proc_macro2::Ident),
+    /// A `_` pattern, which doesn't bind anything that could be printed
+    Ignored,
+}
+
+fn extract_arg_idents(
+    args: &args::Args,
+    attr_applied: AttrApplied,
+    sig: &syn::Signature,
+) -> Result<Vec<ArgIdent>, syn::Error> {
+    // `self` is only printed with `show_self`, unless `enable` or `disable` explicitly mention it
+    fn show_self(args: &args::Args, attr_applied: AttrApplied) -> bool {
+        let self_ident = proc_macro2::Ident::new("self", Span::call_site());
+
+        match (attr_applied, &args.filter) {
+            (AttrApplied::Directly, args::Filter::Enable(ref idents)) => {
+                idents.contains(&self_ident)
+            }
+            (AttrApplied::Directly, args::Filter::Disable(ref idents)) => {
+                args.show_self && !idents.contains(&self_ident)
+            }
+            _ => args.show_self,
+        }
+    }
+
+    fn process_pat(
+        args: &args::Args,
+        attr_applied: AttrApplied,
+        pat: &syn::Pat,
+        arg_idents: &mut Vec<ArgIdent>,
+    ) -> Result<(), syn::Error> {
+        match *pat {
+            syn::Pat::Ident(ref pat_ident) => {
+                let ident = &pat_ident.ident;
+
+                if let Some((_, ref subpat)) = pat_ident.subpat {
+                    process_pat(args, attr_applied, subpat, arg_idents)?;
+                }
+
+                // A typed receiver like `self: Box<Self>`
+                if ident == "self" {
+                    if show_self(args, attr_applied) {
+                        arg_idents.push(ArgIdent::Ident(ident.clone()));
+                    }
+                    return Ok(());
+                }
+
+                if let AttrApplied::Directly = attr_applied {
+                    match args.filter {
+                        args::Filter::Enable(ref idents) if !idents.contains(ident) => {
+                            return Ok(());
+                        }
+                        args::Filter::Disable(ref idents) if idents.contains(ident) => {
+                            return Ok(());
+                        }
+                        _ => (),
+                    }
+                }
+
+                arg_idents.push(ArgIdent::Ident(ident.clone()));
+            }
+            syn::Pat::Wild(_) => {
+                if let AttrApplied::Directly = attr_applied {
+                    if let args::Filter::Enable(_) = args.filter {
+                        return Ok(());
+                    }
+                }
+
+                arg_idents.push(ArgIdent::Ignored);
+            }
+            syn::Pat::Tuple(ref pat_tuple) => {
+                for pat in &pat_tuple.elems {
+                    process_pat(args, attr_applied, pat, arg_idents)?;
+                }
+            }
+            syn::Pat::TupleStruct(ref pat_tuple_struct) => {
+                for pat in &pat_tuple_struct.pat.elems {
+                    process_pat(args, attr_applied, pat, arg_idents)?;
+                }
+            }
+            syn::Pat::Struct(ref pat_struct) => {
+                for field_pat in &pat_struct.fields {
+                    process_pat(args, attr_applied, &field_pat.pat, arg_idents)?;
+                }
+            }
+            syn::Pat::Slice(ref pat_slice) => {
+                for pat in &pat_slice.elems {
+                    process_pat(args, attr_applied, pat, arg_idents)?;
+                }
+            }
+            syn::Pat::Reference(ref pat_reference) => {
+                process_pat(args, attr_applied, &pat_reference.pat, arg_idents)?;
+            }
+            syn::Pat::Box(ref pat_box) => {
+                process_pat(args, attr_applied, &pat_box.pat, arg_idents)?;
+            }
+            syn::Pat::Type(ref pat_type) => {
+                process_pat(args, attr_applied, &pat_type.pat, arg_idents)?;
+            }
+            // Every case of an or-pattern binds the same identifiers
+            syn::Pat::Or(ref pat_or) => {
+                if let Some(pat) = pat_or.cases.first() {
+                    process_pat(args, attr_applied, pat, arg_idents)?;
+                }
+            }
+            // These don't bind anything
+            syn::Pat::Lit(_) | syn::Pat::Path(_) | syn::Pat::Range(_) | syn::Pat::Rest(_) => (),
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    pat,
+                    "#[trace] does not support this argument pattern",
+                ))
+            }
+        }
+
+        Ok(())
+    }
+
+    let mut arg_idents = vec![];
+
+    for input in &sig.inputs {
+        match input {
+            syn::FnArg::Receiver(receiver) => {
+                if show_self(args, attr_applied) {
+                    let ident = proc_macro2::Ident::new("self", receiver.self_token.span);
+                    arg_idents.push(ArgIdent::Ident(ident));
+                }
+            }
+            syn::FnArg::Typed(arg_typed) => {
+                process_pat(args, attr_applied, &arg_typed.pat, &mut arg_idents)?;
+            }
+        }
+    }
+
+    Ok(arg_idents)
+}